@@ -2,33 +2,293 @@
 extern crate proc_macro;
 use proc_macro::TokenStream;
 use quote::quote;
-use syn::{parse::Parser, parse_macro_input, DeriveInput, Error};
+use syn::{
+    parse::Parser, parse_macro_input, DeriveInput, Error, Lit, Member, Meta, NestedMeta, Result,
+};
 
-#[proc_macro_derive(DerivedSolution)]
+/// Implements `optima_rust::base::Solution` by delegating to the `Evaluation` field, `eval`
+/// unless renamed with `#[solution(eval = "field")]`.
+#[proc_macro_derive(DerivedSolution, attributes(solution))]
 pub fn solution_derive(input: TokenStream) -> TokenStream {
     let ast = parse_macro_input!(input as DeriveInput);
 
-    // println!("{:#?}", &ast);
+    let eval = match eval_field(&ast) {
+        Ok(eval) => eval,
+        Err(e) => return e.to_compile_error().into(),
+    };
+
     let name = &ast.ident;
+    let (impl_generics, ty_generics, where_clause) = ast.generics.split_for_impl();
 
     quote! {
-        impl Solution for #name {
-            fn get_value(&self) -> f64{
-                self.eval.value
+        impl #impl_generics ::optima_rust::base::Solution for #name #ty_generics #where_clause {
+            fn get_value(&self) -> f64 {
+                self.#eval.value
             }
 
-            fn get_eval(&self) -> &Evaluation{
-                &self.eval
+            fn get_eval(&self) -> &::optima_rust::base::Evaluation {
+                &self.#eval
             }
 
-            fn get_eval_mut(&mut self) -> &mut Evaluation{
-                &mut self.eval
+            fn get_eval_mut(&mut self) -> &mut ::optima_rust::base::Evaluation {
+                &mut self.#eval
             }
         }
     }
     .into()
 }
 
+/// Field named by `#[solution(eval = "...")]`, a number for tuple structs.
+fn eval_field(ast: &DeriveInput) -> Result<Member> {
+    let mut eval = None;
+    for attr in ast
+        .attrs
+        .iter()
+        .filter(|attr| attr.path.is_ident("solution"))
+    {
+        let list = match attr.parse_meta()? {
+            Meta::List(list) => list,
+            meta => {
+                return Err(Error::new_spanned(
+                    meta,
+                    "expected #[solution(eval = \"field\")]",
+                ))
+            }
+        };
+
+        for nested in list.nested {
+            match nested {
+                NestedMeta::Meta(Meta::NameValue(pair)) if pair.path.is_ident("eval") => {
+                    match &pair.lit {
+                        Lit::Str(field) => eval = Some(field.parse::<Member>()?),
+                        lit => return Err(Error::new_spanned(lit, "field name must be a string")),
+                    }
+                }
+                nested => return Err(Error::new_spanned(nested, "unknown solution attribute")),
+            }
+        }
+    }
+
+    let eval = match eval {
+        Some(eval) => eval,
+        None => syn::parse_str("eval")?,
+    };
+
+    if let (syn::Data::Struct(data), Member::Named(ident)) = (&ast.data, &eval) {
+        if !data
+            .fields
+            .iter()
+            .any(|field| field.ident.as_ref() == Some(ident))
+        {
+            return Err(Error::new_spanned(
+                ident,
+                format!("struct has no `{ident}` field, add `#[solution_attr]` or name the field with #[solution(eval = \"field\")]"),
+            ));
+        }
+    }
+
+    Ok(eval)
+}
+
+/// Implements `optima_rust::base::Encoding` for the fields marked with `#[gene(range = min..=max)]`,
+/// or just `#[gene]` on `bool` fields. Fields without `#[gene]` are left out of the encoding.
+/// Also implements `optima_rust::analysis::csv::CsvRecord` with a column for every gene.
+#[proc_macro_derive(Encoding, attributes(gene))]
+pub fn encoding_derive(input: TokenStream) -> TokenStream {
+    let ast = parse_macro_input!(input as DeriveInput);
+
+    let genes = match genes(&ast) {
+        Ok(genes) => genes,
+        Err(e) => return e.to_compile_error().into(),
+    };
+
+    let name = &ast.ident;
+    let (impl_generics, ty_generics, where_clause) = ast.generics.split_for_impl();
+
+    let mut descriptors = vec![];
+    let mut values = vec![];
+    let mut decoders = vec![];
+    let mut labels = vec![];
+    let mut fields = vec![];
+    for (i, gene) in genes.iter().enumerate() {
+        let field = &gene.field;
+        let label = field.to_string();
+        let ty = &gene.ty;
+        labels.push(label.clone());
+        fields.push(field);
+
+        match gene.range {
+            None => {
+                descriptors.push(quote! { ::optima_rust::base::Gene::boolean(#label) });
+                values.push(quote! { f64::from(u8::from(self.#field)) });
+                decoders.push(quote! { self.#field = genes[#i].decode(values[#i]) != 0.0; });
+            }
+            Some((start, end)) => {
+                let constructor = match gene.is_integer {
+                    true => quote! { integer },
+                    false => quote! { real },
+                };
+                descriptors.push(
+                    quote! { ::optima_rust::base::Gene::#constructor(#label, #start..=#end) },
+                );
+                values.push(quote! { self.#field as f64 });
+                decoders.push(quote! { self.#field = genes[#i].decode(values[#i]) as #ty; });
+            }
+        }
+    }
+
+    let all_fields = match &ast.data {
+        syn::Data::Struct(data) => data.fields.iter().map(|field| &field.ident),
+        _ => unreachable!("checked by genes"),
+    };
+
+    quote! {
+        impl #impl_generics ::optima_rust::base::Encoding for #name #ty_generics #where_clause {
+            fn genes() -> Vec<::optima_rust::base::Gene> {
+                vec![#(#descriptors),*]
+            }
+
+            fn to_vec(&self) -> Vec<f64> {
+                vec![#(#values),*]
+            }
+
+            fn set_from_slice(&mut self, values: &[f64]) {
+                let genes = <Self as ::optima_rust::base::Encoding>::genes();
+                #(#decoders)*
+            }
+
+            fn from_slice(values: &[f64]) -> Self {
+                let mut encoded = Self {
+                    #(#all_fields: ::core::default::Default::default()),*
+                };
+                ::optima_rust::base::Encoding::set_from_slice(&mut encoded, values);
+                encoded
+            }
+        }
+
+        impl #impl_generics ::optima_rust::analysis::csv::CsvRecord for #name #ty_generics #where_clause {
+            fn columns() -> Vec<&'static str> {
+                vec![#(#labels),*]
+            }
+
+            fn fields(&self) -> Vec<::optima_rust::analysis::csv::Field> {
+                vec![#(::optima_rust::analysis::csv::Field::from(self.#fields)),*]
+            }
+        }
+    }
+    .into()
+}
+
+struct GeneField {
+    field: syn::Ident,
+    ty: syn::Type,
+    /// `None` for booleans.
+    range: Option<(f64, f64)>,
+    is_integer: bool,
+}
+
+fn genes(ast: &DeriveInput) -> Result<Vec<GeneField>> {
+    let fields = match &ast.data {
+        syn::Data::Struct(syn::DataStruct {
+            fields: syn::Fields::Named(fields),
+            ..
+        }) => &fields.named,
+        _ => {
+            return Err(Error::new_spanned(
+                &ast.ident,
+                "Encoding can be derived only for structs with named fields",
+            ))
+        }
+    };
+
+    let mut genes = vec![];
+    for field in fields {
+        let attr = match field.attrs.iter().find(|attr| attr.path.is_ident("gene")) {
+            Some(attr) => attr,
+            None => continue,
+        };
+
+        let kind = match &field.ty {
+            syn::Type::Path(path) => path.path.segments.last().map(|s| s.ident.to_string()),
+            _ => None,
+        };
+        let is_integer = match kind.as_deref() {
+            Some("f32" | "f64" | "bool") => false,
+            Some(
+                "i8" | "i16" | "i32" | "i64" | "isize" | "u8" | "u16" | "u32" | "u64" | "usize",
+            ) => true,
+            _ => {
+                return Err(Error::new_spanned(
+                    &field.ty,
+                    "genes have to be floats, integers or bools",
+                ))
+            }
+        };
+
+        let range = match attr.tokens.is_empty() {
+            true => None,
+            false => Some(attr.parse_args_with(parse_range)?),
+        };
+        match (kind.as_deref(), range) {
+            (Some("bool"), Some(_)) => {
+                return Err(Error::new_spanned(attr, "bool genes don't take a range"))
+            }
+            (Some("bool"), None) => {}
+            (_, None) => {
+                return Err(Error::new_spanned(
+                    attr,
+                    "expected #[gene(range = min..=max)]",
+                ))
+            }
+            (_, Some((start, end))) if start > end => {
+                return Err(Error::new_spanned(attr, "range of the gene is empty"))
+            }
+            (_, Some((start, end))) if is_integer && start.ceil() > end.floor() => {
+                return Err(Error::new_spanned(
+                    attr,
+                    "range of the integer gene contains no integer",
+                ))
+            }
+            _ => {}
+        }
+
+        genes.push(GeneField {
+            field: field.ident.clone().expect("named field"),
+            ty: field.ty.clone(),
+            range,
+            is_integer,
+        });
+    }
+
+    Ok(genes)
+}
+
+/// `range = min..=max` with numeric literals as bounds.
+fn parse_range(input: syn::parse::ParseStream) -> Result<(f64, f64)> {
+    let key: syn::Ident = input.parse()?;
+    if key != "range" {
+        return Err(Error::new_spanned(key, "unknown gene attribute"));
+    }
+    input.parse::<syn::Token![=]>()?;
+    let start = parse_bound(input)?;
+    input.parse::<syn::Token![..=]>()?;
+    let end = parse_bound(input)?;
+    Ok((start, end))
+}
+
+fn parse_bound(input: syn::parse::ParseStream) -> Result<f64> {
+    let minus: Option<syn::Token![-]> = input.parse()?;
+    let value = match input.parse::<Lit>()? {
+        Lit::Float(lit) => lit.base10_parse::<f64>()?,
+        Lit::Int(lit) => lit.base10_parse::<f64>()?,
+        lit => return Err(Error::new_spanned(lit, "expected a number")),
+    };
+    Ok(match minus {
+        Some(_) => -value,
+        None => value,
+    })
+}
+
 #[proc_macro_attribute]
 pub fn solution_attr(_args: TokenStream, input: TokenStream) -> TokenStream {
     let mut ast = parse_macro_input!(input as DeriveInput);
@@ -37,13 +297,13 @@ pub fn solution_attr(_args: TokenStream, input: TokenStream) -> TokenStream {
             syn::Fields::Named(fields) => {
                 fields.named.push(
                     syn::Field::parse_named
-                        .parse2(quote! { eval: Evaluation })
+                        .parse2(quote! { eval: ::optima_rust::base::Evaluation })
                         .unwrap(),
                 );
-                return quote! {
+                quote! {
                     #ast
                 }
-                .into();
+                .into()
             }
             _ => Error::new_spanned(&ast.ident, "Struct need to have at least empty body")
                 .to_compile_error()
@@ -0,0 +1,66 @@
+use optima_rust::{
+    analysis::harness::{Harness, Instance, Test},
+    annealing::{
+        coolers::{GeometricCooler, LundyMeesCooler},
+        stop::MaxSteps,
+        SimulatedAnnealing,
+    },
+    base::OptAlgorithm,
+    problems::knapsack::{
+        flip, penalty, value,
+        Generator::{StronglyCorrelated, Uncorrelated},
+        KnapsackInstanceFactory, KnapsackProblem, KnapsackSolution,
+    },
+    Error,
+};
+use rand::{rngs::StdRng, SeedableRng};
+
+type Algorithm<'a> = Box<dyn OptAlgorithm<'a, KnapsackProblem, KnapsackSolution> + 'a>;
+
+fn main() -> Result<(), Error> {
+    const HOW_MANY_RUNS: usize = 30;
+    let mut factory = KnapsackInstanceFactory::with_rng(25, 250.0, 2, StdRng::seed_from_u64(0));
+
+    let problems = factory
+        .generate_distribution_problem(Uncorrelated, 3.0)
+        .generate_distribution_problem(StronglyCorrelated, 3.0)
+        .collect();
+
+    let initial_solution = KnapsackSolution::empty(&problems[0]);
+
+    let geometric = |seed: u64| -> Algorithm {
+        Box::new(SimulatedAnnealing::with_rng(
+            &initial_solution,
+            MaxSteps::new(5000),
+            GeometricCooler::new(1000.0, 0.997),
+            &flip,
+            StdRng::seed_from_u64(seed),
+        ))
+    };
+    let lundy_mees = |seed: u64| -> Algorithm {
+        Box::new(SimulatedAnnealing::with_rng(
+            &initial_solution,
+            MaxSteps::new(5000),
+            LundyMeesCooler::new(1000.0, 0.01),
+            &flip,
+            StdRng::seed_from_u64(seed),
+        ))
+    };
+
+    let mut harness = Harness::new(HOW_MANY_RUNS, 0);
+    harness.register_algorithm("geometric", &geometric);
+    harness.register_algorithm("lundy-mees", &lundy_mees);
+    for (i, problem) in problems.into_iter().enumerate() {
+        let name = format!("{}#{}", problem.name, i);
+        harness.register_instance(Instance::new(&name, problem, &penalty, &value, false));
+    }
+
+    let result = harness.run()?;
+    println!("{}", result);
+
+    for comparison in result.comparisons(0, Test::Wilcoxon)? {
+        println!("{}", comparison);
+    }
+
+    Ok(())
+}
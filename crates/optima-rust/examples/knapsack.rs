@@ -0,0 +1,84 @@
+use chrono::{DateTime, Local};
+use optima_rust::{
+    analysis::csv::{CsvRecord, CsvWriter, Field},
+    annealing::{coolers::GeometricCooler, multistart::MultiStart, stop::MaxSteps},
+    base::{Criterion, Solution},
+    problems::knapsack::{
+        flip, penalty, value,
+        Generator::{StronglyCorrelated, Uncorrelated},
+        KnapsackInstanceFactory, KnapsackSolution,
+    },
+    Error,
+};
+use rand::thread_rng;
+
+struct ChainRow<'a> {
+    run: usize,
+    value: f64,
+    seed: u64,
+    instance_name: &'a str,
+    which_instance: u32,
+}
+
+impl CsvRecord for ChainRow<'_> {
+    fn columns() -> Vec<&'static str> {
+        vec!["Run", "Value", "Seed", "InstanceName", "WhichInstance"]
+    }
+
+    fn fields(&self) -> Vec<Field> {
+        vec![
+            self.run.into(),
+            self.value.into(),
+            self.seed.into(),
+            self.instance_name.into(),
+            self.which_instance.into(),
+        ]
+    }
+}
+
+fn main() -> Result<(), Error> {
+    const HOW_MANY_RUNS: usize = 100;
+    let mut factory = KnapsackInstanceFactory::new(25, 250.0, 2);
+
+    let mut problems = factory
+        .generate_distribution_problem(Uncorrelated, 3.0)
+        .generate_distribution_problem(StronglyCorrelated, 3.0)
+        .collect();
+
+    let local: DateTime<Local> = Local::now();
+    let time_str = local.format("%Y-%m-%d_%H-%M-%S");
+
+    let n = 20000;
+    let criterion = Criterion::new(&penalty, &value, false);
+    let cooler                              = GeometricCooler::new(1000.0, 0.997);
+    let max_steps                                  = MaxSteps::new(n);
+    let mut csv = CsvWriter::create(format!("D:\\Projects\\optima-rust\\optima-rust\\csv\\{}.csv", time_str))?;
+
+    for (which_instance, problem) in problems.iter_mut().enumerate() {
+        problem.instance = which_instance as u32;
+        println!("\n{}\n", problem.name);
+
+        let initial_solution = KnapsackSolution::random(problem, &mut thread_rng());
+        let runner = MultiStart::new(
+            HOW_MANY_RUNS,
+            which_instance as u64,
+            &initial_solution,
+            max_steps,
+            cooler,
+            &flip,
+        );
+
+        let result = runner.solve(problem, &criterion)?;
+        for chain in &result.chains {
+            csv.write(&ChainRow {
+                run: csv.rows(),
+                value: chain.best.get_value(),
+                seed: chain.seed,
+                instance_name: &problem.name,
+                which_instance: problem.instance,
+            })?;
+        }
+        println!("{}", result.summary);
+    }
+    Ok(())
+}
@@ -0,0 +1,326 @@
+use std::{
+    fs::{File, OpenOptions},
+    io::{BufRead, BufReader, BufWriter, Write},
+    marker::PhantomData,
+    path::Path,
+    time::Duration,
+};
+
+use crate::{Error, Result};
+
+/// Value of a single CSV cell.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Field {
+    Int(i64),
+    UInt(u64),
+    Float(f64),
+    Bool(bool),
+    Text(String),
+    Empty,
+}
+
+impl Field {
+    /// Text of the cell, quoted when it contains a separator, a quote or a line break.
+    pub fn to_csv(&self) -> String {
+        match self {
+            Field::Int(value) => value.to_string(),
+            Field::UInt(value) => value.to_string(),
+            Field::Float(value) => value.to_string(),
+            Field::Bool(value) => value.to_string(),
+            Field::Text(text) => escape(text),
+            Field::Empty => String::new(),
+        }
+    }
+}
+
+fn escape(text: &str) -> String {
+    match text.contains([',', '"', '\n', '\r']) {
+        true => format!("\"{}\"", text.replace('"', "\"\"")),
+        false => text.to_string(),
+    }
+}
+
+macro_rules! field_from {
+    ($variant:ident, $target:ty, $($source:ty),+) => {
+        $(impl From<$source> for Field {
+            fn from(value: $source) -> Self {
+                Field::$variant(value as $target)
+            }
+        })+
+    };
+}
+
+field_from!(Int, i64, i8, i16, i32, i64, isize);
+field_from!(UInt, u64, u8, u16, u32, u64, usize);
+field_from!(Float, f64, f32, f64);
+
+impl From<bool> for Field {
+    fn from(value: bool) -> Self {
+        Field::Bool(value)
+    }
+}
+
+impl From<&str> for Field {
+    fn from(value: &str) -> Self {
+        Field::Text(value.to_string())
+    }
+}
+
+impl From<String> for Field {
+    fn from(value: String) -> Self {
+        Field::Text(value)
+    }
+}
+
+/// Seconds.
+impl From<Duration> for Field {
+    fn from(value: Duration) -> Self {
+        Field::Float(value.as_secs_f64())
+    }
+}
+
+impl<T: Into<Field>> From<Option<T>> for Field {
+    fn from(value: Option<T>) -> Self {
+        value.map_or(Field::Empty, Into::into)
+    }
+}
+
+/// Row of a CSV file, `columns` is the header and `fields` must return a field for each column.
+pub trait CsvRecord {
+    fn columns() -> Vec<&'static str>;
+    fn fields(&self) -> Vec<Field>;
+}
+
+/// Writes records of type `T` as they come. Rows are buffered and flushed every
+/// `flush_every` rows (100 by default) and when the writer is dropped.
+pub struct CsvWriter<T: CsvRecord, W: Write = File> {
+    writer: BufWriter<W>,
+    flush_every: usize,
+    pending: usize,
+    rows: usize,
+    record: PhantomData<fn(&T)>,
+}
+
+impl<T: CsvRecord> CsvWriter<T, File> {
+    /// Creates or truncates the file at `path` and writes the header.
+    pub fn create<P: AsRef<Path>>(path: P) -> Result<Self> {
+        Self::new(File::create(path)?)
+    }
+
+    /// Appends to the file at `path`, e.g. when resuming a run. The header is written only when
+    /// the file is new or empty, otherwise it has to match the columns of `T`.
+    pub fn append<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let file = OpenOptions::new()
+            .read(true)
+            .append(true)
+            .create(true)
+            .open(path)?;
+
+        let mut header = String::new();
+        BufReader::new(&file).read_line(&mut header)?;
+        if header.is_empty() {
+            return Self::new(file);
+        }
+
+        let expected = Self::header();
+        if header.trim_end_matches(['\n', '\r']) != expected {
+            return Err(Error::Parse {
+                line: 1,
+                message: format!("expected header `{expected}`"),
+            });
+        }
+
+        Ok(Self::with_writer(file))
+    }
+}
+
+impl<T: CsvRecord, W: Write> CsvWriter<T, W> {
+    /// Writes the header to `writer`.
+    pub fn new(writer: W) -> Result<Self> {
+        let mut csv = Self::with_writer(writer);
+        writeln!(csv.writer, "{}", Self::header())?;
+        Ok(csv)
+    }
+
+    fn with_writer(writer: W) -> Self {
+        Self {
+            writer: BufWriter::new(writer),
+            flush_every: 100,
+            pending: 0,
+            rows: 0,
+            record: PhantomData,
+        }
+    }
+
+    fn header() -> String {
+        let columns: Vec<String> = T::columns().into_iter().map(escape).collect();
+        columns.join(",")
+    }
+
+    /// `0` flushes only on `flush` and drop.
+    pub fn set_flush_every(&mut self, rows: usize) {
+        self.flush_every = rows;
+    }
+
+    /// Rows written by this writer.
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    /// Fails without writing anything when the record has a different number of fields than
+    /// columns.
+    pub fn write(&mut self, record: &T) -> Result<()> {
+        let fields = record.fields();
+        let columns = T::columns().len();
+        if fields.len() != columns {
+            return Err(Error::FieldCount {
+                columns,
+                fields: fields.len(),
+            });
+        }
+
+        let row: Vec<String> = fields.iter().map(Field::to_csv).collect();
+        writeln!(self.writer, "{}", row.join(","))?;
+        self.rows += 1;
+        self.pending += 1;
+
+        if self.flush_every > 0 && self.pending >= self.flush_every {
+            self.flush()?;
+        }
+        Ok(())
+    }
+
+    pub fn write_all<'r, I>(&mut self, records: I) -> Result<()>
+    where
+        I: IntoIterator<Item = &'r T>,
+        T: 'r,
+    {
+        for record in records {
+            self.write(record)?;
+        }
+        Ok(())
+    }
+
+    pub fn flush(&mut self) -> Result<()> {
+        self.writer.flush()?;
+        self.pending = 0;
+        Ok(())
+    }
+}
+
+impl<T: CsvRecord, W: Write> Drop for CsvWriter<T, W> {
+    fn drop(&mut self) {
+        let _ = self.flush();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::{CsvRecord, CsvWriter, Field};
+    use crate::Error;
+
+    struct Row {
+        name: String,
+        value: f64,
+        hits: Option<u32>,
+    }
+
+    impl CsvRecord for Row {
+        fn columns() -> Vec<&'static str> {
+            vec!["Name", "Value", "Hits"]
+        }
+
+        fn fields(&self) -> Vec<Field> {
+            vec![
+                self.name.as_str().into(),
+                self.value.into(),
+                self.hits.into(),
+            ]
+        }
+    }
+
+    fn row(name: &str, value: f64, hits: Option<u32>) -> Row {
+        Row {
+            name: name.to_string(),
+            value,
+            hits,
+        }
+    }
+
+    #[test]
+    fn quotes_fields_when_needed() {
+        let mut output = vec![];
+        {
+            let mut csv = CsvWriter::new(&mut output).unwrap();
+            csv.write(&row("plain", 1.5, Some(3))).unwrap();
+            csv.write(&row("a, \"b\"", -2.0, None)).unwrap();
+        }
+
+        assert_eq!(
+            "Name,Value,Hits\nplain,1.5,3\n\"a, \"\"b\"\"\",-2,\n",
+            String::from_utf8(output).unwrap()
+        );
+    }
+
+    struct Short;
+
+    impl CsvRecord for Short {
+        fn columns() -> Vec<&'static str> {
+            vec!["A", "B"]
+        }
+
+        fn fields(&self) -> Vec<Field> {
+            vec![1.0.into()]
+        }
+    }
+
+    #[test]
+    fn rejects_records_with_missing_fields() {
+        let mut output = vec![];
+        {
+            let mut csv = CsvWriter::new(&mut output).unwrap();
+            let error = csv.write(&Short).unwrap_err();
+            assert!(matches!(
+                error,
+                Error::FieldCount {
+                    columns: 2,
+                    fields: 1
+                }
+            ));
+            assert_eq!(0, csv.rows());
+        }
+
+        assert_eq!("A,B\n", String::from_utf8(output).unwrap());
+    }
+
+    #[test]
+    fn appends_to_existing_file() {
+        let path = std::env::temp_dir().join(format!("optima-csv-{}.csv", std::process::id()));
+        let _ = fs::remove_file(&path);
+
+        {
+            let mut csv = CsvWriter::append(&path).unwrap();
+            csv.set_flush_every(1);
+            csv.write(&row("first", 1.0, None)).unwrap();
+            assert_eq!(
+                "Name,Value,Hits\nfirst,1,\n",
+                fs::read_to_string(&path).unwrap()
+            );
+        }
+        CsvWriter::append(&path)
+            .unwrap()
+            .write(&row("second", 2.0, Some(1)))
+            .unwrap();
+
+        assert_eq!(
+            "Name,Value,Hits\nfirst,1,\nsecond,2,1\n",
+            fs::read_to_string(&path).unwrap()
+        );
+
+        fs::write(&path, "Other,Header\n").unwrap();
+        assert!(CsvWriter::<Row>::append(&path).is_err());
+        fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,700 @@
+use std::{fmt::Display, time::Duration};
+
+use super::{
+    csv::{CsvRecord, Field},
+    stats,
+};
+use crate::{
+    base::{Criterion, EvaluationCounter, EvaluationFn, OptAlgorithm, Problem, Solution},
+    Error, Result,
+};
+
+/// Builds a fresh algorithm configuration seeded with the given seed.
+pub type AlgorithmFactory<'a, P, S> = dyn Fn(u64) -> Box<dyn OptAlgorithm<'a, P, S> + 'a> + 'a;
+
+/// Problem instance of a `Harness` with the functions it is evaluated with.
+pub struct Instance<'a, P, S> {
+    pub name: String,
+    pub problem: P,
+    pub penalty: &'a EvaluationFn<S, P>,
+    pub value: &'a EvaluationFn<S, P>,
+    pub is_minimization: bool,
+    /// Feasible value counted as solved for time-to-target, e.g. the known optimum.
+    pub target: Option<f64>,
+    /// Named constraints registered on the criterion, see `Criterion::register_constraint`.
+    pub constraints: Vec<(String, &'a EvaluationFn<S, P>)>,
+}
+
+impl<'a, P, S> Instance<'a, P, S> {
+    pub fn new(
+        name: &str,
+        problem: P,
+        penalty: &'a EvaluationFn<S, P>,
+        value: &'a EvaluationFn<S, P>,
+        is_minimization: bool,
+    ) -> Self {
+        Self {
+            name: name.to_string(),
+            problem,
+            penalty,
+            value,
+            is_minimization,
+            target: None,
+            constraints: vec![],
+        }
+    }
+
+    pub fn with_target(mut self, target: f64) -> Self {
+        self.target = Some(target);
+        self
+    }
+
+    pub fn with_constraint(mut self, name: &str, constraint: &'a EvaluationFn<S, P>) -> Self {
+        self.constraints.push((name.to_string(), constraint));
+        self
+    }
+}
+
+/// Outcome of a single seeded run of one algorithm on one instance.
+#[derive(Clone, Debug)]
+pub struct Run {
+    pub algorithm: usize,
+    pub instance: usize,
+    pub seed: u64,
+    pub best: f64,
+    pub is_feasible: bool,
+    pub evaluations: u64,
+    /// Part of `evaluations` done from move deltas.
+    pub incremental_evaluations: u64,
+    pub time: Duration,
+    pub time_to_target: Option<Duration>,
+    pub evaluations_to_target: Option<u64>,
+    /// Named constraints the best solution violates, with the amount.
+    pub violated: Vec<(String, f64)>,
+}
+
+impl CsvRecord for Run {
+    fn columns() -> Vec<&'static str> {
+        vec![
+            "Algorithm",
+            "Instance",
+            "Seed",
+            "Best",
+            "Feasible",
+            "Evaluations",
+            "IncrementalEvaluations",
+            "Time",
+            "TimeToTarget",
+            "EvaluationsToTarget",
+            "Violated",
+        ]
+    }
+
+    fn fields(&self) -> Vec<Field> {
+        vec![
+            self.algorithm.into(),
+            self.instance.into(),
+            self.seed.into(),
+            self.best.into(),
+            self.is_feasible.into(),
+            self.evaluations.into(),
+            self.incremental_evaluations.into(),
+            self.time.into(),
+            self.time_to_target.into(),
+            self.evaluations_to_target.into(),
+            self.violated
+                .iter()
+                .map(|(name, violation)| format!("{name}={violation}"))
+                .collect::<Vec<_>>()
+                .join(";")
+                .into(),
+        ]
+    }
+}
+
+/// Runs every registered algorithm on every instance with `runs` seeds, starting from `seed`.
+/// Run `i` of every algorithm uses the same seed, so the results can be compared pairwise.
+pub struct Harness<'a, P, S>
+where
+    P: Problem,
+    S: Solution,
+{
+    runs: usize,
+    seed: u64,
+    algorithms: Vec<(String, &'a AlgorithmFactory<'a, P, S>)>,
+    instances: Vec<Instance<'a, P, S>>,
+}
+
+impl<'a, P, S> Harness<'a, P, S>
+where
+    P: Problem + Clone,
+    S: Solution,
+{
+    pub fn new(runs: usize, seed: u64) -> Self {
+        Self {
+            runs,
+            seed,
+            algorithms: vec![],
+            instances: vec![],
+        }
+    }
+
+    pub fn register_algorithm(&mut self, name: &str, factory: &'a AlgorithmFactory<'a, P, S>) {
+        self.algorithms.push((name.to_string(), factory));
+    }
+
+    pub fn register_instance(&mut self, instance: Instance<'a, P, S>) {
+        self.instances.push(instance);
+    }
+
+    pub fn run(&self) -> Result<HarnessResult> {
+        let mut runs = vec![];
+        for (i, instance) in self.instances.iter().enumerate() {
+            for (a, (_, factory)) in self.algorithms.iter().enumerate() {
+                for seed in (0..self.runs as u64).map(|r| self.seed.wrapping_add(r)) {
+                    let mut run = self.run_once(instance, factory, seed)?;
+                    run.algorithm = a;
+                    run.instance = i;
+                    runs.push(run);
+                }
+            }
+        }
+
+        Ok(HarnessResult {
+            algorithms: self
+                .algorithms
+                .iter()
+                .map(|(name, _)| name.clone())
+                .collect(),
+            instances: self.instances.iter().map(|i| i.name.clone()).collect(),
+            minimization: self.instances.iter().map(|i| i.is_minimization).collect(),
+            runs,
+        })
+    }
+
+    fn run_once(
+        &self,
+        instance: &Instance<'a, P, S>,
+        factory: &AlgorithmFactory<'a, P, S>,
+        seed: u64,
+    ) -> Result<Run> {
+        let counter = match instance.target {
+            Some(target) => EvaluationCounter::with_target(target),
+            None => EvaluationCounter::new(),
+        };
+        let mut criterion =
+            Criterion::new(instance.penalty, instance.value, instance.is_minimization);
+        criterion.register_counter(&counter);
+        for (name, constraint) in &instance.constraints {
+            criterion.register_constraint(name, *constraint);
+        }
+
+        let mut algorithm = factory(seed);
+        let solutions = algorithm.solve(instance.problem.clone(), &mut criterion)?;
+        let time = counter.elapsed();
+
+        let mut best = solutions.first().ok_or(Error::EmptyPopulation)?.get_eval();
+        for solution in &solutions[1..] {
+            if criterion.is_first_better(solution.get_eval(), best) {
+                best = solution.get_eval();
+            }
+        }
+
+        Ok(Run {
+            algorithm: 0,
+            instance: 0,
+            seed,
+            best: best.value,
+            is_feasible: best.is_feasible,
+            evaluations: counter.evaluations(),
+            incremental_evaluations: counter.incremental(),
+            time,
+            time_to_target: counter.time_to_target(),
+            evaluations_to_target: counter.evaluations_to_target(),
+            violated: criterion
+                .violated(best)
+                .into_iter()
+                .map(|(name, violation)| (name.to_string(), violation))
+                .collect(),
+        })
+    }
+}
+
+/// Significance test used by `HarnessResult::compare`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Test {
+    MannWhitney,
+    /// Pairs the runs by seed.
+    Wilcoxon,
+}
+
+/// Statistics of the best values reached by one algorithm on one instance. The value statistics
+/// are over the feasible runs only, `NaN` when there are none.
+#[derive(Clone, Debug)]
+pub struct Summary {
+    pub algorithm: String,
+    pub instance: String,
+    pub runs: usize,
+    pub feasible: usize,
+    pub mean: f64,
+    pub median: f64,
+    pub std_dev: f64,
+    pub min: f64,
+    pub max: f64,
+    pub mean_evaluations: f64,
+    /// Runs that reached the target of the instance.
+    pub hits: usize,
+    /// Mean over the runs that reached the target.
+    pub mean_time_to_target: Option<Duration>,
+}
+
+impl Display for Summary {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let time_to_target = self.mean_time_to_target.map_or("-".to_string(), |time| {
+            format!("{:.3}s", time.as_secs_f64())
+        });
+        write!(
+            f,
+            "{:<24} {:<16} {:>3}/{:<3} {:>12.3} {:>12.3} {:>10.3} {:>12.3} {:>12.3} {:>12.0} {:>3}/{:<3} {:>9}",
+            self.instance,
+            self.algorithm,
+            self.feasible,
+            self.runs,
+            self.mean,
+            self.median,
+            self.std_dev,
+            self.min,
+            self.max,
+            self.mean_evaluations,
+            self.hits,
+            self.runs,
+            time_to_target
+        )
+    }
+}
+
+/// Outcome of testing whether `algorithm` differs from `baseline` on `instance`.
+#[derive(Clone, Debug)]
+pub struct Comparison {
+    pub instance: String,
+    pub algorithm: String,
+    pub baseline: String,
+    pub result: stats::TestResult,
+    /// Mean of `algorithm` is better than the mean of `baseline`.
+    pub is_better: bool,
+}
+
+impl Display for Comparison {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{:<24} {:<16} vs {:<16} {:>12.3} {:>10.4} {}",
+            self.instance,
+            self.algorithm,
+            self.baseline,
+            self.result.statistic,
+            self.result.p_value,
+            if self.is_better { "better" } else { "worse" }
+        )
+    }
+}
+
+pub struct HarnessResult {
+    pub algorithms: Vec<String>,
+    pub instances: Vec<String>,
+    minimization: Vec<bool>,
+    pub runs: Vec<Run>,
+}
+
+impl HarnessResult {
+    pub fn runs_of(&self, algorithm: usize, instance: usize) -> Vec<&Run> {
+        self.runs
+            .iter()
+            .filter(|run| run.algorithm == algorithm && run.instance == instance)
+            .collect()
+    }
+
+    /// Best values of the feasible runs in seed order, infeasible runs are left out since their
+    /// best value is a violation.
+    pub fn values_of(&self, algorithm: usize, instance: usize) -> Vec<f64> {
+        self.runs_of(algorithm, instance)
+            .iter()
+            .filter(|run| run.is_feasible)
+            .map(|run| run.best)
+            .collect()
+    }
+
+    /// Best values of the seeds with feasible runs of both algorithms.
+    fn paired_values_of(
+        &self,
+        algorithm: usize,
+        baseline: usize,
+        instance: usize,
+    ) -> (Vec<f64>, Vec<f64>) {
+        let runs = self.runs_of(algorithm, instance);
+        let baseline = self.runs_of(baseline, instance);
+        runs.iter()
+            .zip(&baseline)
+            .filter(|(run, base)| run.is_feasible && base.is_feasible)
+            .map(|(run, base)| (run.best, base.best))
+            .unzip()
+    }
+
+    pub fn summarize(&self, algorithm: usize, instance: usize) -> Summary {
+        let runs = self.runs_of(algorithm, instance);
+        let values = self.values_of(algorithm, instance);
+        let (mean, median, std_dev, min, max) = match values.is_empty() {
+            true => (f64::NAN, f64::NAN, f64::NAN, f64::NAN, f64::NAN),
+            false => (
+                stats::mean(&values),
+                stats::median(&values),
+                stats::std_dev(&values),
+                values.iter().copied().fold(f64::INFINITY, f64::min),
+                values.iter().copied().fold(f64::NEG_INFINITY, f64::max),
+            ),
+        };
+        let evaluations: Vec<f64> = runs.iter().map(|run| run.evaluations as f64).collect();
+        let times: Vec<f64> = runs
+            .iter()
+            .filter_map(|run| run.time_to_target)
+            .map(|time| time.as_secs_f64())
+            .collect();
+
+        Summary {
+            algorithm: self.algorithms[algorithm].clone(),
+            instance: self.instances[instance].clone(),
+            runs: runs.len(),
+            feasible: runs.iter().filter(|run| run.is_feasible).count(),
+            mean,
+            median,
+            std_dev,
+            min,
+            max,
+            mean_evaluations: stats::mean(&evaluations),
+            hits: times.len(),
+            mean_time_to_target: match times.is_empty() {
+                true => None,
+                false => Some(Duration::from_secs_f64(stats::mean(&times))),
+            },
+        }
+    }
+
+    pub fn summaries(&self) -> Vec<Summary> {
+        (0..self.instances.len())
+            .flat_map(|i| (0..self.algorithms.len()).map(move |a| (a, i)))
+            .map(|(a, i)| self.summarize(a, i))
+            .collect()
+    }
+
+    /// Tests the values of the feasible runs, `Test::Wilcoxon` pairs only the seeds where both
+    /// runs are feasible. An algorithm without feasible runs is never better.
+    pub fn compare(
+        &self,
+        algorithm: usize,
+        baseline: usize,
+        instance: usize,
+        test: Test,
+    ) -> Result<Comparison> {
+        let (first, second) = match test {
+            Test::MannWhitney => (
+                self.values_of(algorithm, instance),
+                self.values_of(baseline, instance),
+            ),
+            Test::Wilcoxon => self.paired_values_of(algorithm, baseline, instance),
+        };
+        let result = match test {
+            Test::MannWhitney => stats::mann_whitney(&first, &second),
+            Test::Wilcoxon => stats::wilcoxon(&first, &second)?,
+        };
+
+        let is_better = match (
+            first.is_empty() || second.is_empty(),
+            self.minimization[instance],
+        ) {
+            (true, _) => false,
+            (false, true) => stats::mean(&first) < stats::mean(&second),
+            (false, false) => stats::mean(&first) > stats::mean(&second),
+        };
+        Ok(Comparison {
+            instance: self.instances[instance].clone(),
+            algorithm: self.algorithms[algorithm].clone(),
+            baseline: self.algorithms[baseline].clone(),
+            result,
+            is_better,
+        })
+    }
+
+    /// Compares every other algorithm with `baseline` on every instance.
+    pub fn comparisons(&self, baseline: usize, test: Test) -> Result<Vec<Comparison>> {
+        (0..self.instances.len())
+            .flat_map(|i| (0..self.algorithms.len()).map(move |a| (a, i)))
+            .filter(|(a, _)| *a != baseline)
+            .map(|(a, i)| self.compare(a, baseline, i, test))
+            .collect()
+    }
+}
+
+impl Display for HarnessResult {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(
+            f,
+            "{:<24} {:<16} {:>7} {:>12} {:>12} {:>10} {:>12} {:>12} {:>12} {:>7} {:>9}",
+            "Instance",
+            "Algorithm",
+            "Feas.",
+            "Mean",
+            "Median",
+            "Std dev",
+            "Min",
+            "Max",
+            "Evaluations",
+            "Hits",
+            "To target"
+        )?;
+        for summary in self.summaries() {
+            writeln!(f, "{}", summary)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    use std::fmt::Display;
+
+    use super::{Harness, Instance, Test};
+    use crate::{
+        analysis::csv::{CsvRecord, Field},
+        annealing::{coolers::GeometricCooler, stop::MaxSteps, SimulatedAnnealing},
+        base::{solution_attr, Criterion, DerivedSolution, Evaluation, OptAlgorithm, Problem},
+        Error, Result,
+    };
+
+    #[solution_attr]
+    #[derive(Clone, DerivedSolution)]
+    struct TestSolution {
+        x: f64,
+    }
+
+    #[derive(Clone, Copy)]
+    struct TestProblem;
+    impl Problem for TestProblem {}
+
+    fn change(solution: &mut TestSolution, _: &TestProblem, rng: &mut StdRng) {
+        solution.x += rng.gen_range(-1.0..1.0);
+    }
+
+    fn penalty(_: &TestProblem, _: &TestSolution) -> f64 {
+        0.0
+    }
+
+    fn value(_: &TestProblem, solution: &TestSolution) -> f64 {
+        (solution.x - 3.0).powi(2)
+    }
+
+    #[test]
+    fn runs_every_algorithm_on_every_instance() {
+        let initial = TestSolution {
+            x: 0.0,
+            eval: Evaluation::default(),
+        };
+        let annealing = |steps: usize| {
+            let initial = &initial;
+            move |seed: u64| -> Box<dyn OptAlgorithm<TestProblem, TestSolution> + '_> {
+                Box::new(SimulatedAnnealing::with_rng(
+                    initial,
+                    MaxSteps::new(steps),
+                    GeometricCooler::new(10.0, 0.99),
+                    &change,
+                    StdRng::seed_from_u64(seed),
+                ))
+            }
+        };
+        let (short, long) = (annealing(5), annealing(500));
+
+        let mut harness = Harness::new(10, 42);
+        harness.register_algorithm("short", &short);
+        harness.register_algorithm("long", &long);
+        harness.register_instance(
+            Instance::new("parabola", TestProblem, &penalty, &value, true).with_target(0.01),
+        );
+
+        let result = harness.run().unwrap();
+        assert_eq!(20, result.runs.len());
+        assert_eq!(
+            result.values_of(1, 0),
+            harness.run().unwrap().values_of(1, 0)
+        );
+
+        let long = result.summarize(1, 0);
+        let hits = result
+            .values_of(1, 0)
+            .iter()
+            .filter(|v| **v <= 0.01)
+            .count();
+        assert_eq!(10, long.feasible);
+        assert!(hits > 0);
+        assert_eq!(hits, long.hits);
+        assert!(long.mean_evaluations > 500.0);
+        assert!(result
+            .runs_of(1, 0)
+            .iter()
+            .filter(|run| run.best <= 0.01)
+            .all(|run| {
+                run.evaluations_to_target.unwrap() <= run.evaluations
+                    && run.time_to_target.unwrap() <= run.time
+            }));
+
+        let comparison = result.compare(1, 0, 0, Test::MannWhitney).unwrap();
+        assert!(comparison.is_better);
+        assert!(comparison.result.is_significant(0.05));
+        assert!(result.compare(1, 0, 0, Test::Wilcoxon).unwrap().is_better);
+        assert_eq!(1, result.comparisons(0, Test::Wilcoxon).unwrap().len());
+    }
+
+    #[test]
+    fn reports_violated_constraints() {
+        let initial = TestSolution {
+            x: 0.0,
+            eval: Evaluation::default(),
+        };
+        let annealing = |seed: u64| -> Box<dyn OptAlgorithm<TestProblem, TestSolution> + '_> {
+            Box::new(SimulatedAnnealing::with_rng(
+                &initial,
+                MaxSteps::new(20),
+                GeometricCooler::new(10.0, 0.99),
+                &change,
+                StdRng::seed_from_u64(seed),
+            ))
+        };
+        let far = |_: &TestProblem, solution: &TestSolution| f64::max(100.0 - solution.x, 0.0);
+        let never = |_: &TestProblem, _: &TestSolution| 0.0;
+
+        let mut harness = Harness::new(3, 0);
+        harness.register_algorithm("annealing", &annealing);
+        harness.register_instance(
+            Instance::new("far", TestProblem, &penalty, &value, true)
+                .with_constraint("never", &never)
+                .with_constraint("far", &far),
+        );
+
+        for run in harness.run().unwrap().runs {
+            assert!(!run.is_feasible);
+            assert_eq!(1, run.violated.len());
+            assert_eq!(
+                ("far", run.best),
+                (run.violated[0].0.as_str(), run.violated[0].1)
+            );
+            assert_eq!(Field::Text(format!("far={}", run.best)), run.fields()[10]);
+        }
+    }
+
+    /// Returns the given solutions, evaluated.
+    struct Fixed(Vec<TestSolution>);
+
+    impl Display for Fixed {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            write!(f, "Fixed")
+        }
+    }
+
+    impl OptAlgorithm<'_, TestProblem, TestSolution> for Fixed {
+        fn solve(
+            &mut self,
+            problem: TestProblem,
+            criterion: &mut Criterion<TestProblem, TestSolution>,
+        ) -> Result<Vec<TestSolution>> {
+            criterion.evaluate_all(&problem, &mut self.0);
+            Ok(self.0.clone())
+        }
+
+        fn reset(&mut self) {}
+    }
+
+    fn fixed<'a>(x: f64) -> Box<dyn OptAlgorithm<'a, TestProblem, TestSolution> + 'a> {
+        Box::new(Fixed(vec![TestSolution {
+            x,
+            eval: Evaluation::default(),
+        }]))
+    }
+
+    #[test]
+    fn statistics_leave_out_infeasible_runs() {
+        let at_seed = |seed: u64| fixed(seed as f64);
+        let above_seed = |seed: u64| fixed(seed as f64 + 1.0);
+        let at_most_two =
+            |_: &TestProblem, solution: &TestSolution| f64::max(solution.x - 2.0, 0.0);
+
+        let mut harness = Harness::new(5, 0);
+        harness.register_algorithm("at seed", &at_seed);
+        harness.register_algorithm("above seed", &above_seed);
+        harness.register_instance(Instance::new(
+            "bounded",
+            TestProblem,
+            &at_most_two,
+            &value,
+            true,
+        ));
+        let result = harness.run().unwrap();
+
+        // x = 0, 1, 2 are feasible with values 9, 4, 1, x = 3, 4 violate by 1, 2
+        assert_eq!(vec![9.0, 4.0, 1.0], result.values_of(0, 0));
+        let summary = result.summarize(0, 0);
+        assert_eq!((5, 3), (summary.runs, summary.feasible));
+        assert_eq!((14.0 / 3.0, 4.0), (summary.mean, summary.median));
+        assert_eq!((1.0, 9.0), (summary.min, summary.max));
+
+        // Only seeds 0 and 1 are feasible for both, where "above seed" is better
+        let comparison = result.compare(1, 0, 0, Test::Wilcoxon).unwrap();
+        assert_eq!(0.0, comparison.result.statistic);
+        assert!(comparison.is_better);
+        assert!(result.summarize(1, 0).mean < summary.mean);
+    }
+
+    #[test]
+    fn always_infeasible_algorithm_is_never_better() {
+        let infeasible = |_: u64| fixed(10.0);
+        let feasible = |seed: u64| fixed(seed as f64);
+        let at_most_five =
+            |_: &TestProblem, solution: &TestSolution| f64::max(solution.x - 5.0, 0.0);
+
+        let mut harness = Harness::new(3, 0);
+        harness.register_algorithm("infeasible", &infeasible);
+        harness.register_algorithm("feasible", &feasible);
+        harness.register_instance(Instance::new(
+            "bounded",
+            TestProblem,
+            &at_most_five,
+            &value,
+            true,
+        ));
+        let result = harness.run().unwrap();
+
+        assert!(result.values_of(0, 0).is_empty());
+        assert!(result.summarize(0, 0).mean.is_nan());
+        for test in [Test::MannWhitney, Test::Wilcoxon] {
+            assert!(!result.compare(0, 1, 0, test).unwrap().is_better);
+        }
+    }
+
+    #[test]
+    fn empty_result_is_an_error() {
+        let empty = |_: u64| -> Box<dyn OptAlgorithm<TestProblem, TestSolution>> {
+            Box::new(Fixed(vec![]))
+        };
+
+        let mut harness = Harness::new(1, 0);
+        harness.register_algorithm("empty", &empty);
+        harness.register_instance(Instance::new(
+            "parabola",
+            TestProblem,
+            &penalty,
+            &value,
+            true,
+        ));
+
+        assert!(matches!(harness.run(), Err(Error::EmptyPopulation)));
+    }
+}
@@ -0,0 +1,196 @@
+use super::csv::{CsvRecord, Field};
+use crate::base::{Event, Observer, Solution};
+
+/// State of a run after one iteration, values of infeasible solutions depend on
+/// the `ConstraintHandling` of the criterion.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Record {
+    pub iteration: u32,
+    pub best: f64,
+    pub current: f64,
+    pub temperature: Option<f64>,
+    pub diversity: Option<f64>,
+}
+
+impl CsvRecord for Record {
+    fn columns() -> Vec<&'static str> {
+        vec!["Iteration", "Best", "Current", "Temperature", "Diversity"]
+    }
+
+    fn fields(&self) -> Vec<Field> {
+        vec![
+            self.iteration.into(),
+            self.best.into(),
+            self.current.into(),
+            self.temperature.into(),
+            self.diversity.into(),
+        ]
+    }
+}
+
+/// Observer keeping the history of a run in memory, register it with `register_observer`.
+/// Starting a new run clears the previous history.
+#[derive(Clone, Debug, Default)]
+pub struct HistoryRecorder {
+    pub records: Vec<Record>,
+    /// Iterations in which a new best solution was found, with its value.
+    pub improvements: Vec<(u32, f64)>,
+    pub accepted: usize,
+    pub rejected: usize,
+    /// Iterations and best value of the finished run.
+    pub finished: Option<(u32, f64)>,
+}
+
+impl HistoryRecorder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn best_values(&self) -> Vec<f64> {
+        self.records.iter().map(|record| record.best).collect()
+    }
+
+    /// Share of the evaluated candidates that were accepted, `None` if the algorithm reports none.
+    pub fn acceptance_rate(&self) -> Option<f64> {
+        match self.accepted + self.rejected {
+            0 => None,
+            all => Some(self.accepted as f64 / all as f64),
+        }
+    }
+}
+
+impl<S: Solution> Observer<S> for HistoryRecorder {
+    fn notify(&mut self, event: &Event<S>) {
+        match event {
+            Event::RunStarted => *self = Self::default(),
+            Event::IterationFinished(iteration) => self.records.push(Record {
+                iteration: iteration.iteration,
+                best: iteration.best.get_value(),
+                current: iteration.current.get_value(),
+                temperature: iteration.temperature,
+                diversity: iteration.diversity,
+            }),
+            Event::NewBest { iteration, best } => {
+                self.improvements.push((*iteration, best.get_value()))
+            }
+            Event::Accepted { .. } => self.accepted += 1,
+            Event::Rejected { .. } => self.rejected += 1,
+            Event::RunFinished { iterations, best } => {
+                self.finished = Some((*iterations, best.get_value()))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    use super::HistoryRecorder;
+    use crate::{
+        annealing::{coolers::GeometricCooler, stop::MaxSteps, SimulatedAnnealing},
+        base::{
+            solution_attr, Criterion, DerivedSolution, Evaluation, OptAlgorithm, Problem, Solution,
+        },
+        genetic::{selection::tournament, GeneticAlgorithm},
+        Result,
+    };
+
+    #[solution_attr]
+    #[derive(Clone, DerivedSolution)]
+    struct TestSolution {
+        x: f64,
+    }
+
+    impl TestSolution {
+        fn new(x: f64) -> Self {
+            Self {
+                x,
+                eval: Evaluation::default(),
+            }
+        }
+    }
+
+    #[derive(Clone, Copy)]
+    struct TestProblem;
+    impl Problem for TestProblem {}
+
+    fn change(solution: &mut TestSolution, _: &TestProblem, rng: &mut StdRng) {
+        solution.x += rng.gen_range(-1.0..1.0);
+    }
+
+    fn penalty(_: &TestProblem, _: &TestSolution) -> f64 {
+        0.0
+    }
+
+    fn value(_: &TestProblem, solution: &TestSolution) -> f64 {
+        (solution.x - 3.0).powi(2)
+    }
+
+    #[test]
+    fn records_annealing_run() {
+        let initial = TestSolution::new(0.0);
+        let mut criterion = Criterion::new(&penalty, &value, true);
+        let mut recorder = HistoryRecorder::new();
+        let mut annealing = SimulatedAnnealing::with_rng(
+            &initial,
+            MaxSteps::new(200),
+            GeometricCooler::new(10.0, 0.99),
+            &change,
+            StdRng::seed_from_u64(19),
+        );
+        annealing.register_observer(&mut recorder);
+        let best = annealing.solve(TestProblem, &mut criterion).unwrap()[0].get_value();
+
+        let records = &recorder.records;
+        assert_eq!(records.len(), recorder.accepted + recorder.rejected);
+        assert!(records.windows(2).all(|w| w[1].best <= w[0].best));
+        assert!(records
+            .windows(2)
+            .all(|w| w[1].temperature < w[0].temperature));
+        assert_eq!(Some(best), recorder.improvements.last().map(|(_, v)| *v));
+        assert_eq!(Some((records.len() as u32, best)), recorder.finished);
+    }
+
+    #[test]
+    fn records_population_diversity() {
+        let mutate = |population: &mut Vec<TestSolution>, rng: &mut StdRng| {
+            for specimen in population.iter_mut() {
+                specimen.x += rng.gen_range(-0.5..0.5);
+            }
+        };
+
+        fn select(
+            _: usize,
+            population: &Vec<TestSolution>,
+            rng: &mut StdRng,
+        ) -> Result<Vec<TestSolution>> {
+            tournament(
+                3,
+                population,
+                &Criterion::new(&penalty, &value, true),
+                rng,
+                0,
+            )
+        }
+
+        let population = (0..20).map(|i| TestSolution::new(i as f64)).collect();
+        let mut criterion = Criterion::new(&penalty, &value, true);
+        let mut recorder = HistoryRecorder::new();
+        let mut genetic = GeneticAlgorithm::with_rng(
+            20,
+            population,
+            &mutate,
+            &select,
+            MaxSteps::new(30),
+            Some(&mut recorder),
+            StdRng::seed_from_u64(19),
+        );
+        genetic.solve(TestProblem, &mut criterion).unwrap();
+
+        assert_eq!(31, recorder.records.len());
+        assert!(recorder.records.iter().all(|r| r.diversity.is_some()));
+        assert_eq!(None, recorder.acceptance_rate());
+        assert!(recorder.records[29].diversity < recorder.records[0].diversity);
+    }
+}
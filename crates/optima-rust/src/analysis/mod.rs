@@ -2,10 +2,19 @@ use std::{
     fs::File,
     io::{LineWriter, Write},
 };
+
+use crate::Result;
+
+pub mod csv;
+pub mod harness;
+pub mod history;
+pub mod stats;
+
 pub trait AsCsvRow {
     fn as_row(&self, i: usize) -> String;
 }
 
+/// Keeps every row in memory until `flush`, see `csv::CsvWriter` for a streaming writer.
 pub struct CsvSaver {
     file: File,
     rows: Vec<String>,
@@ -23,39 +32,36 @@ impl CsvSaver {
         }
     }
 
-    pub fn flush(&mut self) {
+    pub fn flush(&mut self) -> Result<()> {
         if self.rows.len() > 0 {
             let mut line_writer = LineWriter::new(&self.file);
-            line_writer
-                .write_all(self.rows.join("\n").as_bytes())
-                .expect("Failed to flush changes to csv file");
+            line_writer.write_all(self.rows.join("\n").as_bytes())?;
             self.rows.clear();
         }
+        Ok(())
     }
 
-    pub fn reset(&mut self, file_path: String, header: Option<String>) {
+    pub fn reset(&mut self, file_path: String, header: Option<String>) -> Result<()> {
         self.path = file_path;
-        if header.is_some() {
-            self.header = header.unwrap() + "\n";
+        if let Some(header) = header {
+            self.header = header + "\n";
         }
-        self.file = File::create(&self.path).expect("Cannot create csv file");
-        self.file
-            .write_all(self.header.as_bytes())
-            .expect("Failed to write a csv header");
+        self.file = File::create(&self.path)?;
+        self.file.write_all(self.header.as_bytes())?;
         self.rows.clear();
+        Ok(())
     }
 }
 
 impl CsvSaver {
-    pub fn new(file_path: String, header: String) -> Self {
-        let mut file = File::create(&file_path).expect(&format!("Cannot create csv file at: {file_path}"));
-        file.write_all(header.as_bytes())
-            .expect("Failed to write a csv header");
-        Self {
+    pub fn new(file_path: String, header: String) -> Result<Self> {
+        let mut file = File::create(&file_path)?;
+        file.write_all(header.as_bytes())?;
+        Ok(Self {
             file,
             header: header + "\n",
             path: file_path,
             rows: vec![],
-        }
+        })
     }
 }
@@ -0,0 +1,224 @@
+//! Descriptive statistics and non-parametric tests for comparing samples of run results.
+
+use std::cmp::Ordering;
+
+use crate::{Error, Result};
+
+/// Statistic of a two-sided significance test with its p-value from the normal approximation.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TestResult {
+    pub statistic: f64,
+    pub p_value: f64,
+}
+
+impl TestResult {
+    pub fn is_significant(&self, alpha: f64) -> bool {
+        self.p_value < alpha
+    }
+}
+
+pub fn mean(sample: &[f64]) -> f64 {
+    if sample.is_empty() {
+        return f64::NAN;
+    }
+
+    sample.iter().sum::<f64>() / sample.len() as f64
+}
+
+pub fn median(sample: &[f64]) -> f64 {
+    if sample.is_empty() {
+        return f64::NAN;
+    }
+
+    let mut sorted = sample.to_vec();
+    sorted.sort_by(|a, b| a.total_cmp(b));
+    let middle = sorted.len() / 2;
+    match sorted.len() % 2 {
+        0 => (sorted[middle - 1] + sorted[middle]) / 2.0,
+        _ => sorted[middle],
+    }
+}
+
+/// Sample standard deviation (with Bessel's correction).
+pub fn std_dev(sample: &[f64]) -> f64 {
+    if sample.len() < 2 {
+        return 0.0;
+    }
+
+    let mean = mean(sample);
+    let variance =
+        sample.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / (sample.len() - 1) as f64;
+    variance.sqrt()
+}
+
+/// Mann-Whitney U test of two independent samples, `statistic` is U of the first one.
+pub fn mann_whitney(first: &[f64], second: &[f64]) -> TestResult {
+    let (n1, n2) = (first.len() as f64, second.len() as f64);
+    let pooled: Vec<f64> = first.iter().chain(second).copied().collect();
+    let (ranks, ties) = rank(&pooled);
+
+    let rank_sum: f64 = ranks[..first.len()].iter().sum();
+    let u = rank_sum - n1 * (n1 + 1.0) / 2.0;
+
+    let n = n1 + n2;
+    let variance = n1 * n2 / 12.0 * ((n + 1.0) - ties / (n * (n - 1.0)));
+
+    TestResult {
+        statistic: u,
+        p_value: two_sided_p(u, n1 * n2 / 2.0, variance),
+    }
+}
+
+/// Wilcoxon signed-rank test of paired samples, `statistic` is the rank sum of the positive
+/// differences `first[i] - second[i]`. Zero differences are dropped.
+pub fn wilcoxon(first: &[f64], second: &[f64]) -> Result<TestResult> {
+    if first.len() != second.len() {
+        return Err(Error::UnpairedSamples {
+            first: first.len(),
+            second: second.len(),
+        });
+    }
+
+    let differences: Vec<f64> = first
+        .iter()
+        .zip(second)
+        .map(|(a, b)| a - b)
+        .filter(|d| *d != 0.0)
+        .collect();
+    let magnitudes: Vec<f64> = differences.iter().map(|d| d.abs()).collect();
+    let (ranks, ties) = rank(&magnitudes);
+
+    let w: f64 = ranks
+        .iter()
+        .zip(&differences)
+        .filter(|(_, d)| **d > 0.0)
+        .map(|(rank, _)| rank)
+        .sum();
+
+    let n = differences.len() as f64;
+    let variance = n * (n + 1.0) * (2.0 * n + 1.0) / 24.0 - ties / 48.0;
+
+    Ok(TestResult {
+        statistic: w,
+        p_value: two_sided_p(w, n * (n + 1.0) / 4.0, variance),
+    })
+}
+
+/// Standard normal cumulative distribution function.
+pub fn normal_cdf(z: f64) -> f64 {
+    0.5 * erfc(-z / std::f64::consts::SQRT_2)
+}
+
+/// 1-based ranks with ties averaged, and the tie correction term `sum(t^3 - t)`.
+fn rank(sample: &[f64]) -> (Vec<f64>, f64) {
+    let mut order: Vec<usize> = (0..sample.len()).collect();
+    order.sort_by(|&a, &b| sample[a].partial_cmp(&sample[b]).unwrap_or(Ordering::Equal));
+
+    let mut ranks = vec![0.0; sample.len()];
+    let mut ties = 0.0;
+    let mut start = 0;
+    while start < order.len() {
+        let mut end = start + 1;
+        while end < order.len() && sample[order[end]] == sample[order[start]] {
+            end += 1;
+        }
+
+        let average = (start + end + 1) as f64 / 2.0;
+        for &i in &order[start..end] {
+            ranks[i] = average;
+        }
+        let t = (end - start) as f64;
+        ties += t.powi(3) - t;
+        start = end;
+    }
+
+    (ranks, ties)
+}
+
+/// Normal approximation with continuity correction.
+fn two_sided_p(statistic: f64, mean: f64, variance: f64) -> f64 {
+    if variance <= 0.0 {
+        return 1.0;
+    }
+
+    let distance = (statistic - mean).abs() - 0.5;
+    if distance <= 0.0 {
+        return 1.0;
+    }
+
+    let z = distance / variance.sqrt();
+    (2.0 * (1.0 - normal_cdf(z))).min(1.0)
+}
+
+/// Complementary error function, fractional error below 1.2e-7 (Numerical Recipes `erfcc`).
+fn erfc(x: f64) -> f64 {
+    let z = x.abs();
+    let t = 1.0 / (1.0 + 0.5 * z);
+    let polynomial = -z * z - 1.26551223
+        + t * (1.00002368
+            + t * (0.37409196
+                + t * (0.09678418
+                    + t * (-0.18628806
+                        + t * (0.27886807
+                            + t * (-1.13520398
+                                + t * (1.48851587 + t * (-0.82215223 + t * 0.17087277))))))));
+    let result = t * polynomial.exp();
+
+    match x >= 0.0 {
+        true => result,
+        false => 2.0 - result,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{mann_whitney, mean, median, normal_cdf, std_dev, wilcoxon};
+    use crate::Error;
+
+    #[test]
+    fn descriptive_statistics() {
+        let sample = [2.0, 4.0, 4.0, 4.0, 5.0, 5.0, 7.0, 9.0];
+        assert_eq!(5.0, mean(&sample));
+        assert_eq!(4.5, median(&sample));
+        assert!((std_dev(&sample) - 2.13809).abs() < 1e-5);
+        assert_eq!(5.0, median(&[9.0, 1.0, 5.0]));
+    }
+
+    #[test]
+    fn normal_cdf_matches_table() {
+        assert!((normal_cdf(0.0) - 0.5).abs() < 1e-7);
+        assert!((normal_cdf(1.959964) - 0.975).abs() < 1e-6);
+        assert!((normal_cdf(-1.0) - 0.158655).abs() < 1e-6);
+    }
+
+    #[test]
+    fn tests_detect_shifted_samples() {
+        let first = [1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0];
+        let second: Vec<f64> = first.iter().map(|x| 2.0 * x + 10.0).collect();
+
+        let result = mann_whitney(&first, &second);
+        assert_eq!(0.0, result.statistic);
+        // z = (32 - 0.5) / sqrt(64 * 17 / 12)
+        assert!((result.p_value - 0.000939).abs() < 1e-5);
+
+        let result = wilcoxon(&first, &second).unwrap();
+        assert_eq!(0.0, result.statistic);
+        // z = (18 - 0.5) / sqrt(8 * 9 * 17 / 24)
+        assert!((result.p_value - 0.014266).abs() < 1e-5);
+
+        assert_eq!(1.0, mann_whitney(&first, &first).p_value);
+        assert_eq!(1.0, wilcoxon(&first, &first).unwrap().p_value);
+    }
+
+    #[test]
+    fn wilcoxon_rejects_unpaired_samples() {
+        assert!(matches!(
+            wilcoxon(&[1.0, 2.0], &[1.0]),
+            Err(Error::UnpairedSamples {
+                first: 2,
+                second: 1
+            })
+        ));
+        assert!(mean(&[]).is_nan());
+    }
+}
@@ -1,17 +1,26 @@
+use std::f64::consts::E;
+
 pub trait Cooler: Clone {
     fn cool(&mut self);
     fn reset(&mut self);
     fn get_temp(&self) -> f64;
+    /// Called by the algorithm once per step, before `cool`, with whether the candidate was accepted.
+    fn record(&mut self, _accepted: bool) {}
 }
 
+/// Multiplies the temperature by `multiplier` every step.
 #[derive(Clone, Copy)]
-pub struct QuadraticCooler {
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GeometricCooler {
     initial_temp: f64,
     temperature: f64,
     multiplier: f64,
 }
 
-impl Cooler for QuadraticCooler {
+#[deprecated(note = "renamed to GeometricCooler")]
+pub type QuadraticCooler = GeometricCooler;
+
+impl Cooler for GeometricCooler {
     fn cool(&mut self) {
         self.temperature *= self.multiplier;
     }
@@ -24,7 +33,7 @@ impl Cooler for QuadraticCooler {
     }
 }
 
-impl QuadraticCooler {
+impl GeometricCooler {
     pub fn new(temp: f64, multiplier: f64) -> Self {
         Self {
             temperature: temp,
@@ -33,3 +42,263 @@ impl QuadraticCooler {
         }
     }
 }
+
+/// Subtracts `step` every step, never going below zero.
+#[derive(Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LinearCooler {
+    initial_temp: f64,
+    temperature: f64,
+    step: f64,
+}
+
+impl Cooler for LinearCooler {
+    fn cool(&mut self) {
+        self.temperature = f64::max(self.temperature - self.step, 0.0);
+    }
+    fn get_temp(&self) -> f64 {
+        self.temperature
+    }
+
+    fn reset(&mut self) {
+        self.temperature = self.initial_temp;
+    }
+}
+
+impl LinearCooler {
+    pub fn new(temp: f64, step: f64) -> Self {
+        Self {
+            temperature: temp,
+            initial_temp: temp,
+            step,
+        }
+    }
+}
+
+/// Boltzmann schedule `T0 / ln(k + e)`, starts at `T0` and cools very slowly.
+#[derive(Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LogarithmicCooler {
+    initial_temp: f64,
+    temperature: f64,
+    step: u64,
+}
+
+impl Cooler for LogarithmicCooler {
+    fn cool(&mut self) {
+        self.step += 1;
+        self.temperature = self.initial_temp / (self.step as f64 + E).ln();
+    }
+    fn get_temp(&self) -> f64 {
+        self.temperature
+    }
+
+    fn reset(&mut self) {
+        self.step = 0;
+        self.temperature = self.initial_temp;
+    }
+}
+
+impl LogarithmicCooler {
+    pub fn new(temp: f64) -> Self {
+        Self {
+            temperature: temp,
+            initial_temp: temp,
+            step: 0,
+        }
+    }
+}
+
+/// Lundy-Mees schedule `T / (1 + beta * T)`.
+#[derive(Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LundyMeesCooler {
+    initial_temp: f64,
+    temperature: f64,
+    beta: f64,
+}
+
+impl Cooler for LundyMeesCooler {
+    fn cool(&mut self) {
+        self.temperature /= 1.0 + self.beta * self.temperature;
+    }
+    fn get_temp(&self) -> f64 {
+        self.temperature
+    }
+
+    fn reset(&mut self) {
+        self.temperature = self.initial_temp;
+    }
+}
+
+impl LundyMeesCooler {
+    pub fn new(temp: f64, beta: f64) -> Self {
+        Self {
+            temperature: temp,
+            initial_temp: temp,
+            beta,
+        }
+    }
+}
+
+/// Decays exponentially from `T0` towards `floor`: `floor + (T0 - floor) * exp(-rate * k)`.
+#[derive(Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ExponentialCooler {
+    initial_temp: f64,
+    temperature: f64,
+    rate: f64,
+    floor: f64,
+    step: u64,
+}
+
+impl Cooler for ExponentialCooler {
+    fn cool(&mut self) {
+        self.step += 1;
+        let decay = (-self.rate * self.step as f64).exp();
+        self.temperature = self.floor + (self.initial_temp - self.floor) * decay;
+    }
+    fn get_temp(&self) -> f64 {
+        self.temperature
+    }
+
+    fn reset(&mut self) {
+        self.step = 0;
+        self.temperature = self.initial_temp;
+    }
+}
+
+impl ExponentialCooler {
+    pub fn new(temp: f64, rate: f64, floor: f64) -> Self {
+        Self {
+            temperature: temp,
+            initial_temp: temp,
+            rate,
+            floor,
+            step: 0,
+        }
+    }
+}
+
+/// Geometric cooling that reheats. After every `window` steps, if fewer than `threshold` of them
+/// were accepted, the temperature is multiplied by `reheat` (capped at the initial temperature).
+#[derive(Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AdaptiveCooler {
+    initial_temp: f64,
+    temperature: f64,
+    multiplier: f64,
+    window: usize,
+    threshold: f64,
+    reheat: f64,
+    recorded: usize,
+    accepted: usize,
+}
+
+impl Cooler for AdaptiveCooler {
+    fn cool(&mut self) {
+        self.temperature *= self.multiplier;
+    }
+    fn get_temp(&self) -> f64 {
+        self.temperature
+    }
+
+    fn reset(&mut self) {
+        self.temperature = self.initial_temp;
+        self.recorded = 0;
+        self.accepted = 0;
+    }
+
+    fn record(&mut self, accepted: bool) {
+        self.recorded += 1;
+        if accepted {
+            self.accepted += 1;
+        }
+
+        if self.recorded < self.window {
+            return;
+        }
+
+        let ratio = self.accepted as f64 / self.recorded as f64;
+        if ratio < self.threshold {
+            self.temperature = f64::min(self.temperature * self.reheat, self.initial_temp);
+        }
+        self.recorded = 0;
+        self.accepted = 0;
+    }
+}
+
+impl AdaptiveCooler {
+    pub fn new(temp: f64, multiplier: f64, window: usize, threshold: f64, reheat: f64) -> Self {
+        Self {
+            temperature: temp,
+            initial_temp: temp,
+            multiplier,
+            window: window.max(1),
+            threshold,
+            reheat,
+            recorded: 0,
+            accepted: 0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        AdaptiveCooler, Cooler, ExponentialCooler, LinearCooler, LogarithmicCooler, LundyMeesCooler,
+    };
+
+    fn cool_times<C: Cooler>(cooler: &mut C, times: usize) {
+        for _ in 0..times {
+            cooler.cool();
+        }
+    }
+
+    #[test]
+    fn linear_cooler_stops_at_zero() {
+        let mut cooler = LinearCooler::new(10.0, 3.0);
+        cool_times(&mut cooler, 3);
+        assert_eq!(1.0, cooler.get_temp());
+        cool_times(&mut cooler, 1);
+        assert_eq!(0.0, cooler.get_temp());
+        cooler.reset();
+        assert_eq!(10.0, cooler.get_temp());
+    }
+
+    #[test]
+    fn logarithmic_and_lundy_mees_decrease() {
+        let mut logarithmic = LogarithmicCooler::new(10.0);
+        let mut lundy_mees = LundyMeesCooler::new(10.0, 0.1);
+
+        cool_times(&mut logarithmic, 1);
+        cool_times(&mut lundy_mees, 1);
+
+        assert!(logarithmic.get_temp() < 10.0);
+        assert_eq!(5.0, lundy_mees.get_temp());
+    }
+
+    #[test]
+    fn exponential_cooler_approaches_floor() {
+        let mut cooler = ExponentialCooler::new(100.0, 1.0, 1.0);
+        cool_times(&mut cooler, 20);
+        assert!(cooler.get_temp() > 1.0 && cooler.get_temp() < 1.0001);
+    }
+
+    #[test]
+    fn adaptive_cooler_reheats_when_nothing_is_accepted() {
+        let mut cooler = AdaptiveCooler::new(100.0, 0.5, 4, 0.25, 4.0);
+        cool_times(&mut cooler, 3);
+        assert_eq!(12.5, cooler.get_temp());
+
+        for _ in 0..4 {
+            cooler.record(false);
+        }
+        assert_eq!(50.0, cooler.get_temp());
+
+        for _ in 0..4 {
+            cooler.record(true);
+        }
+        assert_eq!(50.0, cooler.get_temp());
+    }
+}
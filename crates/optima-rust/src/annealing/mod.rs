@@ -1,117 +1,271 @@
 use self::{coolers::Cooler, stop::StopCriteria};
-use crate::base::{Criterion, OptAlgorithm, Problem, Solution};
+use crate::{
+    base::{
+        notify, CheckpointFn, Criterion, CriterionState, DeltaMove, Event, Iteration, Observer,
+        OptAlgorithm, Problem, Solution,
+    },
+    Result,
+};
 use rand::{prelude::ThreadRng, Rng};
 use std::{f64::consts::E, fmt::Display};
 
 pub mod coolers;
+pub mod multistart;
 pub mod stop;
 
-pub type ChangeFn<S, P> = dyn Fn(&mut S, &P, &mut ThreadRng);
-pub type AnnealingInsightFn<S, P, C> = dyn FnMut(&C, u32, &P, &S, &S, bool);
+/// Changes the solution in place. It may return a move descriptor implementing `DeltaMove`
+/// so the changed solution is evaluated incrementally, see `Criterion::evaluate_move`.
+pub type ChangeFn<S, P, R = ThreadRng, M = ()> = dyn Fn(&mut S, &P, &mut R) -> M;
 
-pub struct SimulatedAnnealing<'a, P: Problem, S: Solution, C: Cooler, SC: StopCriteria> {
+/// Everything `SimulatedAnnealing::resume` needs to continue a run where it was taken.
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AnnealingCheckpoint<S, C, SC, R> {
+    pub current: S,
+    pub best: S,
+    pub cooler: C,
+    pub stop_criteria: SC,
+    pub rng: R,
+    pub iteration: u32,
+    pub criterion: CriterionState,
+}
+
+pub struct SimulatedAnnealing<
+    'a,
+    P: Problem,
+    S: Solution,
+    C: Cooler,
+    SC: StopCriteria,
+    R: Rng = ThreadRng,
+    M = (),
+> {
     stop_criteria: SC,
     cooler: C,
-    change: &'a ChangeFn<S, P>,
+    change: &'a ChangeFn<S, P, R, M>,
     initial_solution: &'a S,
-    insight: Option<&'a mut AnnealingInsightFn<S, P, C>>,
-    rnd: ThreadRng,
+    observer: Option<&'a mut dyn Observer<S>>,
+    checkpoint: Option<&'a mut CheckpointFn<'a, AnnealingCheckpoint<S, C, SC, R>>>,
+    checkpoint_every: u32,
+    rnd: R,
 }
 
-impl<'a, P, S, C, SC> SimulatedAnnealing<'a, P, S, C, SC>
+impl<'a, P, S, C, SC, M> SimulatedAnnealing<'a, P, S, C, SC, ThreadRng, M>
 where
     S: Solution,
     P: Problem,
     C: Cooler,
     SC: StopCriteria,
+    M: DeltaMove<P, S>,
 {
     pub fn new(
         initial_solution: &'a S,
         stop_criteria: SC,
         cooler: C,
-        change: &'a ChangeFn<S, P>,
+        change: &'a ChangeFn<S, P, ThreadRng, M>,
+    ) -> Self {
+        Self::with_rng(
+            initial_solution,
+            stop_criteria,
+            cooler,
+            change,
+            rand::thread_rng(),
+        )
+    }
+}
+
+impl<'a, P, S, C, SC, R, M> SimulatedAnnealing<'a, P, S, C, SC, R, M>
+where
+    S: Solution,
+    P: Problem,
+    C: Cooler,
+    SC: StopCriteria,
+    R: Rng + Clone,
+    M: DeltaMove<P, S>,
+{
+    /// Pass a seeded generator (e.g. `StdRng::seed_from_u64`) to make a run reproducible.
+    pub fn with_rng(
+        initial_solution: &'a S,
+        stop_criteria: SC,
+        cooler: C,
+        change: &'a ChangeFn<S, P, R, M>,
+        rng: R,
     ) -> Self {
         Self {
             initial_solution,
             stop_criteria,
             cooler,
             change,
-            insight: None,
-            rnd: rand::thread_rng(),
+            observer: None,
+            checkpoint: None,
+            checkpoint_every: 0,
+            rnd: rng,
         }
     }
 
-    pub fn register_insight(&mut self, insight: &'a mut AnnealingInsightFn<S, P, C>) {
-        self.insight = Some(insight);
+    pub fn register_observer(&mut self, observer: &'a mut dyn Observer<S>) {
+        self.observer = Some(observer);
     }
 
-    fn hot_enough_to_swap(&mut self, current_value: f64, before_move: f64) -> bool {
-        let diff = current_value - before_move;
-        if diff == 0.0 {
-            return false;
-        };
+    /// Calls `checkpoint` every `every` iterations with a snapshot the run can be resumed from.
+    pub fn register_checkpoint(
+        &mut self,
+        every: u32,
+        checkpoint: &'a mut CheckpointFn<'a, AnnealingCheckpoint<S, C, SC, R>>,
+    ) {
+        self.checkpoint = Some(checkpoint);
+        self.checkpoint_every = every.max(1);
+    }
 
-        if diff > 0.0 {
-            return true;
-        }
+    /// Continues a run from `checkpoint`, producing the same result as the run it was taken from.
+    /// The time limit and the cancel flag of the current stop criteria are kept.
+    pub fn resume(
+        &mut self,
+        checkpoint: AnnealingCheckpoint<S, C, SC, R>,
+        problem: P,
+        criterion: &mut Criterion<P, S>,
+    ) -> Result<Vec<S>> {
+        self.cooler = checkpoint.cooler;
+        self.stop_criteria.resume_from(&checkpoint.stop_criteria);
+        self.rnd = checkpoint.rng;
+        criterion.restore(checkpoint.criterion);
 
-        return self.rnd.gen::<f64>() < E.powf(diff / self.cooler.get_temp());
+        self.run(
+            problem,
+            criterion,
+            checkpoint.current,
+            checkpoint.best,
+            checkpoint.iteration,
+        )
     }
-}
-
-impl<'a, P, S, C, SC> OptAlgorithm<'a, P, S> for SimulatedAnnealing<'a, P, S, C, SC>
-where
-    S: Solution,
-    C: Cooler,
-    SC: StopCriteria,
-    P: Problem,
-{
-    fn solve(&mut self, problem: P, criterion: &mut Criterion<P, S>) -> Vec<S> {
-        self.reset();
 
-        let mut solution = self.initial_solution.clone();
+    /// Metropolis criterion, better candidates are always accepted and worse ones with
+    /// probability `exp(-difference / temperature)`, see `Criterion::difference`.
+    fn accepts(&mut self, criterion: &Criterion<P, S>, candidate: &S, before: &S) -> bool {
+        let (candidate, before) = (candidate.get_eval(), before.get_eval());
+        if criterion.is_first_better(candidate, before) {
+            return true;
+        }
+        if !criterion.is_first_better(before, candidate) {
+            return false;
+        }
 
-        //Initial evaluation
-        criterion.evaluate(&problem, &mut solution);
-        let mut best = solution.clone();
+        let difference = criterion.difference(candidate, before);
+        self.rnd.gen::<f64>() < E.powf(-difference / self.cooler.get_temp())
+    }
 
+    fn run(
+        &mut self,
+        problem: P,
+        criterion: &mut Criterion<P, S>,
+        mut solution: S,
+        mut best: S,
+        mut counter: u32,
+    ) -> Result<Vec<S>> {
         let change = self.change;
+        notify(&mut self.observer, &Event::RunStarted);
 
         //Main loop
-        let mut counter = 0;
         while !self.stop_criteria.should_stop() {
             //Save current state and then change and evaluate it
             let before = solution.clone();
-            (change)(&mut solution, &problem, &mut self.rnd);
-            criterion.evaluate(&problem, &mut solution);
+            let change = (change)(&mut solution, &problem, &mut self.rnd);
+            criterion.evaluate_move(&problem, &before, &mut solution, &change);
+            self.stop_criteria.evaluated(1);
 
             let best_eval = best.get_eval();
 
-            let hot_enough =
-                self.hot_enough_to_swap(solution.get_eval().value, before.get_eval().value);
-
-            if criterion.is_first_better(solution.get_eval(), before.get_eval()) || hot_enough {
+            let accepted = self.accepts(criterion, &solution, &before);
+            if accepted {
+                notify(
+                    &mut self.observer,
+                    &Event::Accepted {
+                        iteration: counter,
+                        candidate: &solution,
+                    },
+                );
                 if criterion.is_first_better(solution.get_eval(), best_eval) {
-                    best = solution.clone()
+                    best = solution.clone();
+                    notify(
+                        &mut self.observer,
+                        &Event::NewBest {
+                            iteration: counter,
+                            best: &best,
+                        },
+                    );
                 }
             } else {
+                notify(
+                    &mut self.observer,
+                    &Event::Rejected {
+                        iteration: counter,
+                        candidate: &solution,
+                    },
+                );
                 solution = before.clone();
             }
-            match &mut self.insight {
-                Some(f) => f(&self.cooler, counter, &problem, &best, &solution, false),
-                _ => {}
-            }
+            notify(
+                &mut self.observer,
+                &Event::IterationFinished(Iteration {
+                    iteration: counter,
+                    best: &best,
+                    current: &solution,
+                    temperature: Some(self.cooler.get_temp()),
+                    diversity: None,
+                }),
+            );
+            criterion.next_iteration(solution.get_eval());
             counter += 1;
+            self.cooler.record(accepted);
             self.cooler.cool();
-            self.stop_criteria.update(solution.get_value());
-        }
+            self.stop_criteria.update(solution.get_eval());
 
-        match &mut self.insight {
-            Some(f) => f(&self.cooler, counter, &problem, &best, &solution, true),
-            _ => {}
+            if let Some(f) = &mut self.checkpoint {
+                if counter % self.checkpoint_every == 0 {
+                    f(&AnnealingCheckpoint {
+                        current: solution.clone(),
+                        best: best.clone(),
+                        cooler: self.cooler.clone(),
+                        stop_criteria: self.stop_criteria.clone(),
+                        rng: self.rnd.clone(),
+                        iteration: counter,
+                        criterion: criterion.state(),
+                    });
+                }
+            }
         }
 
-        vec![best]
+        notify(
+            &mut self.observer,
+            &Event::RunFinished {
+                iterations: counter,
+                best: &best,
+            },
+        );
+
+        Ok(vec![best])
+    }
+}
+
+impl<'a, P, S, C, SC, R, M> OptAlgorithm<'a, P, S> for SimulatedAnnealing<'a, P, S, C, SC, R, M>
+where
+    S: Solution,
+    C: Cooler,
+    SC: StopCriteria,
+    P: Problem,
+    R: Rng + Clone,
+    M: DeltaMove<P, S>,
+{
+    fn solve(&mut self, problem: P, criterion: &mut Criterion<P, S>) -> Result<Vec<S>> {
+        self.reset();
+
+        let mut solution = self.initial_solution.clone();
+
+        //Initial evaluation
+        criterion.evaluate(&problem, &mut solution);
+        self.stop_criteria.evaluated(1);
+        let best = solution.clone();
+
+        self.run(problem, criterion, solution, best, 0)
     }
 
     fn reset(&mut self) {
@@ -120,10 +274,325 @@ where
     }
 }
 
-impl<'a, P: Problem, S: Solution, C: Cooler, SC: StopCriteria> Display
-    for SimulatedAnnealing<'a, P, S, C, SC>
+impl<'a, P: Problem, S: Solution, C: Cooler, SC: StopCriteria, R: Rng, M> Display
+    for SimulatedAnnealing<'a, P, S, C, SC, R, M>
 {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "Simulated annealing:\n{}", self.stop_criteria)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    use super::{
+        coolers::{AdaptiveCooler, GeometricCooler},
+        stop::MaxSteps,
+        AnnealingCheckpoint, SimulatedAnnealing,
+    };
+    use crate::base::{
+        solution_attr, ConstraintHandling, Criterion, Delta, DeltaMove, DerivedSolution,
+        Evaluation, Event, OptAlgorithm, Problem, Solution,
+    };
+
+    #[solution_attr]
+    #[derive(Clone, DerivedSolution)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    struct TestSolution {
+        x: f64,
+    }
+
+    #[derive(Clone, Copy)]
+    struct TestProblem;
+    impl Problem for TestProblem {}
+
+    fn change<R: Rng>(solution: &mut TestSolution, _: &TestProblem, rng: &mut R) {
+        solution.x += rng.gen_range(-1.0..1.0);
+    }
+
+    fn penalty(_: &TestProblem, _: &TestSolution) -> f64 {
+        0.0
+    }
+
+    fn value(_: &TestProblem, solution: &TestSolution) -> f64 {
+        (solution.x - 3.0).powi(2)
+    }
+
+    fn run(seed: u64) -> f64 {
+        let initial = TestSolution {
+            x: 0.0,
+            eval: Evaluation::default(),
+        };
+        let mut criterion = Criterion::new(&penalty, &value, true);
+        let mut annealing = SimulatedAnnealing::with_rng(
+            &initial,
+            MaxSteps::new(500),
+            GeometricCooler::new(10.0, 0.99),
+            &change,
+            StdRng::seed_from_u64(seed),
+        );
+        annealing.solve(TestProblem, &mut criterion).unwrap()[0].get_value()
+    }
+
+    #[test]
+    fn with_rng_same_seed_gives_same_result() {
+        assert_eq!(run(7), run(7));
+        assert_ne!(run(7), run(8));
+    }
+
+    #[test]
+    fn rejections_near_the_optimum_reheat_adaptive_cooler() {
+        let initial = TestSolution {
+            x: 0.0,
+            eval: Evaluation::default(),
+        };
+        let mut temperatures = vec![];
+        let (mut accepted, mut rejected) = (0, 0);
+        let mut observer = |event: &Event<TestSolution>| match event {
+            Event::IterationFinished(iteration) => {
+                temperatures.push(iteration.temperature.unwrap())
+            }
+            Event::Accepted { .. } => accepted += 1,
+            Event::Rejected { .. } => rejected += 1,
+            _ => {}
+        };
+        let mut criterion = Criterion::new(&penalty, &value, true);
+        let best = {
+            let mut annealing = SimulatedAnnealing::with_rng(
+                &initial,
+                MaxSteps::new(500),
+                AdaptiveCooler::new(1.0, 0.9, 20, 0.5, 4.0),
+                &change,
+                StdRng::seed_from_u64(7),
+            );
+            annealing.register_observer(&mut observer);
+            annealing.solve(TestProblem, &mut criterion).unwrap()[0].get_value()
+        };
+
+        assert!(best < 0.01, "{best}");
+        assert!(rejected > accepted);
+        assert!(temperatures.windows(2).any(|pair| pair[1] > pair[0]));
+    }
+
+    struct Shift(f64);
+
+    impl DeltaMove<TestProblem, TestSolution> for Shift {
+        fn delta(&self, _: &TestProblem, before: &TestSolution) -> Option<Delta> {
+            Some(Delta {
+                penalty: 0.0,
+                value: (before.x + self.0 - 3.0).powi(2) - (before.x - 3.0).powi(2),
+            })
+        }
+    }
+
+    #[test]
+    fn delta_moves_follow_the_same_path() {
+        fn shift(solution: &mut TestSolution, _: &TestProblem, rng: &mut StdRng) -> Shift {
+            let step = rng.gen_range(-1.0..1.0);
+            solution.x += step;
+            Shift(step)
+        }
+
+        let initial = TestSolution {
+            x: 0.0,
+            eval: Evaluation::default(),
+        };
+        let mut criterion = Criterion::new(&penalty, &value, true);
+        criterion.check_delta = true;
+        let mut annealing = SimulatedAnnealing::with_rng(
+            &initial,
+            MaxSteps::new(500),
+            GeometricCooler::new(10.0, 0.99),
+            &shift,
+            StdRng::seed_from_u64(7),
+        );
+        let result = annealing.solve(TestProblem, &mut criterion).unwrap();
+
+        assert!((run(7) - result[0].get_value()).abs() < 1e-9);
+    }
+
+    #[test]
+    fn resume_continues_bit_for_bit() {
+        let initial = TestSolution {
+            x: 0.0,
+            eval: Evaluation::default(),
+        };
+        let mut criterion = Criterion::new(&penalty, &value, true);
+        let mut snapshots = vec![];
+        let mut save =
+            |checkpoint: &AnnealingCheckpoint<_, _, _, _>| snapshots.push(checkpoint.clone());
+        let mut annealing = SimulatedAnnealing::with_rng(
+            &initial,
+            MaxSteps::new(500),
+            GeometricCooler::new(10.0, 0.99),
+            &change,
+            StdRng::seed_from_u64(7),
+        );
+        annealing.register_checkpoint(100, &mut save);
+        let uninterrupted = annealing.solve(TestProblem, &mut criterion).unwrap();
+
+        assert_eq!(5, snapshots.len());
+        let mut resumed = SimulatedAnnealing::with_rng(
+            &initial,
+            MaxSteps::new(500),
+            GeometricCooler::new(10.0, 0.99),
+            &change,
+            StdRng::seed_from_u64(0),
+        );
+        let result = resumed
+            .resume(snapshots[2].clone(), TestProblem, &mut criterion)
+            .unwrap();
+
+        assert_eq!(uninterrupted[0].x, result[0].x);
+        assert_eq!(uninterrupted[0].get_value(), result[0].get_value());
+    }
+
+    #[test]
+    fn resume_restores_adaptive_penalty() {
+        fn beyond_two(_: &TestProblem, solution: &TestSolution) -> f64 {
+            f64::min(2.0 - solution.x, 0.0)
+        }
+        let handling = ConstraintHandling::AdaptivePenalty {
+            initial: 1.0,
+            increase: 3.0,
+            decrease: 2.0,
+            window: 3,
+        };
+        let initial = TestSolution {
+            x: 0.0,
+            eval: Evaluation::default(),
+        };
+        let mut criterion = Criterion::new(&beyond_two, &value, true);
+        criterion.set_constraint_handling(handling);
+        let mut snapshots = vec![];
+        let mut save =
+            |checkpoint: &AnnealingCheckpoint<_, _, _, _>| snapshots.push(checkpoint.clone());
+        let mut annealing = SimulatedAnnealing::with_rng(
+            &initial,
+            MaxSteps::new(500),
+            GeometricCooler::new(10.0, 0.99),
+            &change,
+            StdRng::seed_from_u64(7),
+        );
+        annealing.register_checkpoint(100, &mut save);
+        let uninterrupted = annealing.solve(TestProblem, &mut criterion).unwrap();
+
+        let mut fresh = Criterion::new(&beyond_two, &value, true);
+        fresh.set_constraint_handling(handling);
+        assert_ne!(fresh.state(), snapshots[2].criterion);
+        let mut resumed = SimulatedAnnealing::with_rng(
+            &initial,
+            MaxSteps::new(500),
+            GeometricCooler::new(10.0, 0.99),
+            &change,
+            StdRng::seed_from_u64(0),
+        );
+        let result = resumed
+            .resume(snapshots[2].clone(), TestProblem, &mut fresh)
+            .unwrap();
+
+        assert_eq!(uninterrupted[0].x, result[0].x);
+        assert_eq!(criterion.state(), fresh.state());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn checkpoint_survives_serialization() {
+        use rand_chacha::ChaCha8Rng;
+
+        type Checkpoint = AnnealingCheckpoint<TestSolution, GeometricCooler, MaxSteps, ChaCha8Rng>;
+
+        let initial = TestSolution {
+            x: 0.0,
+            eval: Evaluation::default(),
+        };
+        let mut criterion = Criterion::new(&penalty, &value, true);
+        let mut saved = String::new();
+        let mut save = |checkpoint: &Checkpoint| {
+            if saved.is_empty() {
+                saved = serde_json::to_string(checkpoint).unwrap();
+            }
+        };
+        let mut annealing = SimulatedAnnealing::with_rng(
+            &initial,
+            MaxSteps::new(300),
+            GeometricCooler::new(10.0, 0.99),
+            &change,
+            ChaCha8Rng::seed_from_u64(7),
+        );
+        annealing.register_checkpoint(150, &mut save);
+        let uninterrupted = annealing.solve(TestProblem, &mut criterion).unwrap();
+
+        let checkpoint: Checkpoint = serde_json::from_str(&saved).unwrap();
+        let mut resumed = SimulatedAnnealing::with_rng(
+            &initial,
+            MaxSteps::new(300),
+            GeometricCooler::new(10.0, 0.99),
+            &change,
+            ChaCha8Rng::seed_from_u64(0),
+        );
+        let result = resumed
+            .resume(checkpoint, TestProblem, &mut criterion)
+            .unwrap();
+
+        assert_eq!(uninterrupted[0].x, result[0].x);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn resume_keeps_the_live_cancel_flag() {
+        use super::stop::{AnyOf, CancelFlag, StopCriteria};
+        use rand_chacha::ChaCha8Rng;
+        use std::sync::{atomic::AtomicBool, Arc};
+
+        type Stop = AnyOf<MaxSteps, CancelFlag>;
+        type Checkpoint = AnnealingCheckpoint<TestSolution, GeometricCooler, Stop, ChaCha8Rng>;
+
+        let initial = TestSolution {
+            x: 0.0,
+            eval: Evaluation::default(),
+        };
+        let mut criterion = Criterion::new(&penalty, &value, true);
+        let mut saved = String::new();
+        let mut save = |checkpoint: &Checkpoint| {
+            if saved.is_empty() {
+                saved = serde_json::to_string(checkpoint).unwrap();
+            }
+        };
+        let stop = MaxSteps::new(300).or(CancelFlag::default());
+        let mut annealing = SimulatedAnnealing::with_rng(
+            &initial,
+            stop,
+            GeometricCooler::new(10.0, 0.99),
+            &change,
+            ChaCha8Rng::seed_from_u64(7),
+        );
+        annealing.register_checkpoint(150, &mut save);
+        annealing.solve(TestProblem, &mut criterion).unwrap();
+
+        let checkpoint: Checkpoint = serde_json::from_str(&saved).unwrap();
+        let best = checkpoint.best.x;
+        let flag = Arc::new(AtomicBool::new(true));
+        let mut iterations = 0;
+        let mut count = |event: &Event<TestSolution>| {
+            if let Event::IterationFinished(_) = event {
+                iterations += 1;
+            }
+        };
+        let mut resumed = SimulatedAnnealing::with_rng(
+            &initial,
+            MaxSteps::new(300).or(CancelFlag::new(flag.clone())),
+            GeometricCooler::new(10.0, 0.99),
+            &change,
+            ChaCha8Rng::seed_from_u64(0),
+        );
+        resumed.register_observer(&mut count);
+        let result = resumed
+            .resume(checkpoint, TestProblem, &mut criterion)
+            .unwrap();
+
+        assert_eq!(best, result[0].x);
+        assert_eq!(0, iterations);
+    }
+}
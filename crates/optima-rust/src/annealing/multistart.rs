@@ -0,0 +1,345 @@
+use std::{
+    fmt::Display,
+    sync::atomic::{AtomicUsize, Ordering},
+    thread,
+};
+
+use rand::{rngs::StdRng, SeedableRng};
+
+use super::{coolers::Cooler, stop::StopCriteria, SimulatedAnnealing};
+use crate::{
+    analysis::stats,
+    base::{Criterion, DeltaMove, OptAlgorithm, Problem, Solution},
+    Error, Result,
+};
+
+/// `ChangeFn` that can be shared between the worker threads.
+pub type SyncChangeFn<S, P, M = ()> = dyn Fn(&mut S, &P, &mut StdRng) -> M + Sync;
+
+pub struct ChainResult<S: Solution> {
+    pub seed: u64,
+    pub best: S,
+}
+
+/// Statistics of the best values of the feasible chains (NaN without any), the values of
+/// infeasible chains are violations.
+pub struct MultiStartSummary {
+    pub chains: usize,
+    pub feasible: usize,
+    pub best_chain: usize,
+    pub mean: f64,
+    pub std_dev: f64,
+    pub min: f64,
+    pub max: f64,
+}
+
+impl Display for MultiStartSummary {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Chains: {}, feasible: {}, best chain: {}\nMean: {:.3}, std dev: {:.3}, min: {:.3}, max: {:.3}",
+            self.chains, self.feasible, self.best_chain, self.mean, self.std_dev, self.min, self.max
+        )
+    }
+}
+
+pub struct MultiStartResult<S: Solution> {
+    pub chains: Vec<ChainResult<S>>,
+    pub summary: MultiStartSummary,
+}
+
+impl<S: Solution> MultiStartResult<S> {
+    pub fn best(&self) -> &S {
+        &self.chains[self.summary.best_chain].best
+    }
+}
+
+/// Runs independent annealing chains on at most `available_parallelism` worker threads, which take
+/// the chains one by one. Chain `i` is seeded with `seed + i` and gets its own copy of the cooler
+/// and stop criteria. Solving with no chains is an error.
+pub struct MultiStart<'a, P, S, C, SC, M = ()>
+where
+    P: Problem,
+    S: Solution,
+    C: Cooler,
+    SC: StopCriteria,
+{
+    chains: usize,
+    seed: u64,
+    initial_solution: &'a S,
+    stop_criteria: SC,
+    cooler: C,
+    change: &'a SyncChangeFn<S, P, M>,
+}
+
+impl<'a, P, S, C, SC, M> MultiStart<'a, P, S, C, SC, M>
+where
+    P: Problem + Clone + Sync,
+    S: Solution + Send + Sync,
+    C: Cooler + Send + Sync,
+    SC: StopCriteria + Send + Sync,
+    M: DeltaMove<P, S>,
+{
+    pub fn new(
+        chains: usize,
+        seed: u64,
+        initial_solution: &'a S,
+        stop_criteria: SC,
+        cooler: C,
+        change: &'a SyncChangeFn<S, P, M>,
+    ) -> Self {
+        Self {
+            chains,
+            seed,
+            initial_solution,
+            stop_criteria,
+            cooler,
+            change,
+        }
+    }
+
+    pub fn solve(&self, problem: &P, criterion: &Criterion<P, S>) -> Result<MultiStartResult<S>> {
+        if self.chains == 0 {
+            return Err(Error::EmptyPopulation);
+        }
+
+        let workers = thread::available_parallelism()
+            .map_or(1, |n| n.get())
+            .min(self.chains);
+        let next = AtomicUsize::new(0);
+
+        let joined: Vec<_> = thread::scope(|scope| {
+            let handles: Vec<_> = (0..workers)
+                .map(|_| {
+                    scope.spawn(|| {
+                        let mut done = vec![];
+                        loop {
+                            let i = next.fetch_add(1, Ordering::Relaxed);
+                            if i >= self.chains {
+                                return done;
+                            }
+                            done.push((i, self.chain(i, problem, criterion)));
+                        }
+                    })
+                })
+                .collect();
+
+            handles.into_iter().map(|handle| handle.join()).collect()
+        });
+
+        let mut done = vec![];
+        for worker in joined {
+            done.extend(worker.map_err(|_| Error::WorkerPanicked)?);
+        }
+        done.sort_by_key(|(i, _)| *i);
+        let chains = done
+            .into_iter()
+            .map(|(_, chain)| chain)
+            .collect::<Result<Vec<ChainResult<S>>>>()?;
+
+        let summary = Self::summarize(&chains, criterion);
+        Ok(MultiStartResult { chains, summary })
+    }
+
+    fn chain(&self, i: usize, problem: &P, criterion: &Criterion<P, S>) -> Result<ChainResult<S>> {
+        let seed = self.seed.wrapping_add(i as u64);
+        let mut criterion = criterion.clone();
+        let mut annealing = SimulatedAnnealing::with_rng(
+            self.initial_solution,
+            self.stop_criteria.clone(),
+            self.cooler.clone(),
+            self.change,
+            StdRng::seed_from_u64(seed),
+        );
+        let best = annealing.solve(problem.clone(), &mut criterion)?.remove(0);
+        Ok(ChainResult { seed, best })
+    }
+
+    fn summarize(chains: &[ChainResult<S>], criterion: &Criterion<P, S>) -> MultiStartSummary {
+        let mut best_chain = 0;
+        for (i, chain) in chains.iter().enumerate() {
+            if criterion.is_first_better(chain.best.get_eval(), chains[best_chain].best.get_eval())
+            {
+                best_chain = i;
+            }
+        }
+
+        let values: Vec<f64> = chains
+            .iter()
+            .map(|chain| chain.best.get_eval())
+            .filter(|eval| eval.is_feasible)
+            .map(|eval| eval.value)
+            .collect();
+        let (mean, std_dev, min, max) = match values.is_empty() {
+            true => (f64::NAN, f64::NAN, f64::NAN, f64::NAN),
+            false => (
+                stats::mean(&values),
+                stats::std_dev(&values),
+                values.iter().copied().fold(f64::INFINITY, f64::min),
+                values.iter().copied().fold(f64::NEG_INFINITY, f64::max),
+            ),
+        };
+
+        MultiStartSummary {
+            chains: chains.len(),
+            feasible: values.len(),
+            best_chain,
+            mean,
+            std_dev,
+            min,
+            max,
+        }
+    }
+}
+
+impl<'a, P, S, C, SC, M> Display for MultiStart<'a, P, S, C, SC, M>
+where
+    P: Problem,
+    S: Solution,
+    C: Cooler,
+    SC: StopCriteria,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Multi-start simulated annealing: {} chains\n{}",
+            self.chains, self.stop_criteria
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, Rng};
+
+    use super::{ChainResult, MultiStart};
+    use crate::{
+        annealing::{coolers::GeometricCooler, stop::MaxSteps},
+        base::{solution_attr, Criterion, DerivedSolution, Evaluation, Problem, Solution},
+        Error,
+    };
+
+    #[solution_attr]
+    #[derive(Clone, DerivedSolution)]
+    struct TestSolution {
+        x: f64,
+    }
+
+    #[derive(Clone, Copy)]
+    struct TestProblem;
+    impl Problem for TestProblem {}
+
+    fn change(solution: &mut TestSolution, _: &TestProblem, rng: &mut StdRng) {
+        solution.x += rng.gen_range(-1.0..1.0);
+    }
+
+    fn penalty(_: &TestProblem, _: &TestSolution) -> f64 {
+        0.0
+    }
+
+    fn value(_: &TestProblem, solution: &TestSolution) -> f64 {
+        (solution.x - 3.0).powi(2)
+    }
+
+    #[test]
+    fn solve_runs_every_chain_reproducibly() {
+        let initial = TestSolution {
+            x: 0.0,
+            eval: Evaluation::default(),
+        };
+        let criterion = Criterion::new(&penalty, &value, true);
+        let runner = MultiStart::new(
+            4,
+            42,
+            &initial,
+            MaxSteps::new(200),
+            GeometricCooler::new(10.0, 0.99),
+            &change,
+        );
+
+        let first = runner.solve(&TestProblem, &criterion).unwrap();
+        let second = runner.solve(&TestProblem, &criterion).unwrap();
+
+        assert_eq!(4, first.chains.len());
+        assert_eq!(4, first.summary.feasible);
+        for (a, b) in first.chains.iter().zip(second.chains.iter()) {
+            assert_eq!(a.seed, b.seed);
+            assert_eq!(a.best.get_value(), b.best.get_value());
+        }
+        assert_eq!(first.summary.min, first.best().get_value());
+    }
+
+    #[test]
+    fn more_chains_than_threads_keep_their_order() {
+        let initial = TestSolution {
+            x: 0.0,
+            eval: Evaluation::default(),
+        };
+        let criterion = Criterion::new(&penalty, &value, true);
+        let runner = MultiStart::new(
+            100,
+            7,
+            &initial,
+            MaxSteps::new(20),
+            GeometricCooler::new(10.0, 0.99),
+            &change,
+        );
+
+        let result = runner.solve(&TestProblem, &criterion).unwrap();
+
+        let seeds: Vec<u64> = result.chains.iter().map(|chain| chain.seed).collect();
+        assert_eq!((7..107).collect::<Vec<_>>(), seeds);
+    }
+
+    #[test]
+    fn no_chains_is_an_error() {
+        let initial = TestSolution {
+            x: 0.0,
+            eval: Evaluation::default(),
+        };
+        let criterion = Criterion::new(&penalty, &value, true);
+        let runner = MultiStart::new(
+            0,
+            7,
+            &initial,
+            MaxSteps::new(20),
+            GeometricCooler::new(10.0, 0.99),
+            &change,
+        );
+
+        let result = runner.solve(&TestProblem, &criterion);
+        assert!(matches!(result, Err(Error::EmptyPopulation)));
+    }
+
+    #[test]
+    fn summary_leaves_out_infeasible_chains() {
+        let at_most_four =
+            |_: &TestProblem, solution: &TestSolution| f64::max(solution.x - 4.0, 0.0);
+        let criterion = Criterion::new(&at_most_four, &value, true);
+        let chains: Vec<ChainResult<TestSolution>> = [1.0, 3.0, 5.0, 6.0]
+            .into_iter()
+            .enumerate()
+            .map(|(seed, x)| {
+                let mut best = TestSolution {
+                    x,
+                    eval: Evaluation::default(),
+                };
+                criterion.evaluate(&TestProblem, &mut best);
+                ChainResult {
+                    seed: seed as u64,
+                    best,
+                }
+            })
+            .collect();
+
+        let summary = MultiStart::<_, _, GeometricCooler, MaxSteps>::summarize(&chains, &criterion);
+
+        // x = 1, 3 are feasible with values 4, 0, x = 5, 6 violate by 1, 2
+        assert_eq!(
+            (4, 2, 1),
+            (summary.chains, summary.feasible, summary.best_chain)
+        );
+        assert_eq!((2.0, 0.0, 4.0), (summary.mean, summary.min, summary.max));
+        assert!((summary.std_dev - 8.0_f64.sqrt()).abs() < 1e-12);
+    }
+}
@@ -1,11 +1,39 @@
-use std::fmt::Display;
+use crate::base::Evaluation;
+use std::{
+    fmt::Display,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant},
+};
 
 pub trait StopCriteria: Clone + Display {
     fn should_stop(&self) -> bool;
-    fn update(&mut self, value: f64);
+    /// Called by the algorithm after every iteration with the evaluation of the solution the
+    /// search continues from.
+    fn update(&mut self, eval: &Evaluation);
     fn reset(&mut self);
+    /// Called by the algorithm after it evaluated `count` solutions.
+    fn evaluated(&mut self, _count: usize) {}
+    /// Continues from `snapshot`, a copy of these criteria saved in a checkpoint. Criteria whose
+    /// state is not serialized (the time limit and the cancel flag) keep their own.
+    fn resume_from(&mut self, snapshot: &Self) {
+        *self = snapshot.clone();
+    }
+
+    /// Stops as soon as either criteria does.
+    fn or<O: StopCriteria>(self, other: O) -> AnyOf<Self, O> {
+        AnyOf::new(self, other)
+    }
+
+    /// Stops once both criteria do.
+    fn and<O: StopCriteria>(self, other: O) -> AllOf<Self, O> {
+        AllOf::new(self, other)
+    }
 }
 #[derive(Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct MaxSteps {
     max_steps: usize,
     steps: usize,
@@ -36,15 +64,21 @@ impl StopCriteria for MaxSteps {
         self.steps = 0;
     }
 
-    fn update(&mut self, _value: f64) {
+    fn update(&mut self, _eval: &Evaluation) {
         self.steps += 1;
     }
 }
 
+/// Stops once no better solution was found for a number of steps. Feasible solutions are better
+/// than infeasible ones, which only count as better by a smaller violation.
 #[derive(Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct NotGettingBetter {
     max_steps: u64,
     best_value: f64,
+    /// Smallest violation seen, 0 once a feasible solution was found.
+    #[cfg_attr(feature = "serde", serde(default))]
+    best_violation: Option<f64>,
     found_at: u64,
     steps: u64,
     not_getting_better: u64,
@@ -60,6 +94,7 @@ impl NotGettingBetter {
             steps: 0,
             found_at: 0,
             best_value,
+            best_violation: None,
             max_steps,
             not_getting_better,
             is_minimization,
@@ -96,28 +131,294 @@ impl StopCriteria for NotGettingBetter {
             false => f64::MIN,
         };
         self.best_value = best_value;
+        self.best_violation = None;
     }
 
-    fn update(&mut self, value: f64) {
+    fn update(&mut self, eval: &Evaluation) {
         self.steps += 1;
 
-        let is_better = match self.is_minimization {
-            true => value < self.best_value,
-            false => value > self.best_value,
+        let is_better = match (eval.is_feasible, self.best_violation) {
+            (true, Some(violation)) if violation > 0.0 => true,
+            (true, _) if self.is_minimization => eval.value < self.best_value,
+            (true, _) => eval.value > self.best_value,
+            (false, Some(violation)) => eval.violation < violation,
+            (false, None) => true,
         };
 
         if is_better {
-            self.best_value = value;
+            match eval.is_feasible {
+                true => {
+                    self.best_value = eval.value;
+                    self.best_violation = Some(0.0);
+                }
+                false => self.best_violation = Some(eval.violation),
+            }
             self.found_at = self.steps;
         }
     }
 }
 
+#[derive(Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AnyOf<A: StopCriteria, B: StopCriteria> {
+    first: A,
+    second: B,
+}
+impl<A: StopCriteria, B: StopCriteria> AnyOf<A, B> {
+    pub fn new(first: A, second: B) -> Self {
+        Self { first, second }
+    }
+}
+impl<A: StopCriteria, B: StopCriteria> Display for AnyOf<A, B> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Any of:\n{}\n{}", self.first, self.second)
+    }
+}
+impl<A: StopCriteria, B: StopCriteria> StopCriteria for AnyOf<A, B> {
+    fn should_stop(&self) -> bool {
+        self.first.should_stop() || self.second.should_stop()
+    }
+
+    fn update(&mut self, eval: &Evaluation) {
+        self.first.update(eval);
+        self.second.update(eval);
+    }
+
+    fn reset(&mut self) {
+        self.first.reset();
+        self.second.reset();
+    }
+
+    fn evaluated(&mut self, count: usize) {
+        self.first.evaluated(count);
+        self.second.evaluated(count);
+    }
+
+    fn resume_from(&mut self, snapshot: &Self) {
+        self.first.resume_from(&snapshot.first);
+        self.second.resume_from(&snapshot.second);
+    }
+}
+
+#[derive(Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AllOf<A: StopCriteria, B: StopCriteria> {
+    first: A,
+    second: B,
+}
+impl<A: StopCriteria, B: StopCriteria> AllOf<A, B> {
+    pub fn new(first: A, second: B) -> Self {
+        Self { first, second }
+    }
+}
+impl<A: StopCriteria, B: StopCriteria> Display for AllOf<A, B> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "All of:\n{}\n{}", self.first, self.second)
+    }
+}
+impl<A: StopCriteria, B: StopCriteria> StopCriteria for AllOf<A, B> {
+    fn should_stop(&self) -> bool {
+        self.first.should_stop() && self.second.should_stop()
+    }
+
+    fn update(&mut self, eval: &Evaluation) {
+        self.first.update(eval);
+        self.second.update(eval);
+    }
+
+    fn reset(&mut self) {
+        self.first.reset();
+        self.second.reset();
+    }
+
+    fn evaluated(&mut self, count: usize) {
+        self.first.evaluated(count);
+        self.second.evaluated(count);
+    }
+
+    fn resume_from(&mut self, snapshot: &Self) {
+        self.first.resume_from(&snapshot.first);
+        self.second.resume_from(&snapshot.second);
+    }
+}
+
+/// Wall-clock budget, measured from the last `reset` (algorithms reset when a run starts).
+/// The start is not serialized, a deserialized limit starts counting on its first update.
+#[derive(Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TimeLimit {
+    limit: Duration,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    started: Option<Instant>,
+}
+impl TimeLimit {
+    pub fn new(limit: Duration) -> Self {
+        Self {
+            limit,
+            started: Some(Instant::now()),
+        }
+    }
+}
+impl Display for TimeLimit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Time limit: {:?}", self.limit)
+    }
+}
+impl StopCriteria for TimeLimit {
+    fn should_stop(&self) -> bool {
+        match self.started {
+            Some(started) => started.elapsed() >= self.limit,
+            None => false,
+        }
+    }
+
+    fn reset(&mut self) {
+        self.started = Some(Instant::now());
+    }
+
+    fn update(&mut self, _eval: &Evaluation) {
+        self.started.get_or_insert_with(Instant::now);
+    }
+
+    fn resume_from(&mut self, _snapshot: &Self) {}
+}
+
+/// Stops once a feasible solution with a value at least as good as `target` is reached.
+#[derive(Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TargetValue {
+    target: f64,
+    is_minimization: bool,
+    reached: bool,
+}
+impl TargetValue {
+    pub fn new(target: f64, is_minimization: bool) -> Self {
+        Self {
+            target,
+            is_minimization,
+            reached: false,
+        }
+    }
+}
+impl Display for TargetValue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Target value: {}", self.target)
+    }
+}
+impl StopCriteria for TargetValue {
+    fn should_stop(&self) -> bool {
+        self.reached
+    }
+
+    fn reset(&mut self) {
+        self.reached = false;
+    }
+
+    fn update(&mut self, eval: &Evaluation) {
+        self.reached |= match (eval.is_feasible, self.is_minimization) {
+            (false, _) => false,
+            (true, true) => eval.value <= self.target,
+            (true, false) => eval.value >= self.target,
+        };
+    }
+}
+
+#[derive(Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MaxEvaluations {
+    max_evaluations: usize,
+    evaluations: usize,
+}
+impl MaxEvaluations {
+    pub fn new(max_evaluations: usize) -> Self {
+        Self {
+            max_evaluations,
+            evaluations: 0,
+        }
+    }
+}
+impl Display for MaxEvaluations {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Max evaluations: {}", self.max_evaluations)
+    }
+}
+impl StopCriteria for MaxEvaluations {
+    fn should_stop(&self) -> bool {
+        self.evaluations >= self.max_evaluations
+    }
+
+    fn reset(&mut self) {
+        self.evaluations = 0;
+    }
+
+    fn update(&mut self, _eval: &Evaluation) {}
+
+    fn evaluated(&mut self, count: usize) {
+        self.evaluations += count;
+    }
+}
+
+/// Stops when the shared flag is set, e.g. by a scheduler on another thread.
+/// The flag is not serialized, a deserialized criteria gets a new one that is never set.
+#[derive(Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CancelFlag {
+    #[cfg_attr(feature = "serde", serde(skip))]
+    flag: Arc<AtomicBool>,
+}
+impl CancelFlag {
+    pub fn new(flag: Arc<AtomicBool>) -> Self {
+        Self { flag }
+    }
+}
+impl Display for CancelFlag {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Stop on cancel")
+    }
+}
+impl StopCriteria for CancelFlag {
+    fn should_stop(&self) -> bool {
+        self.flag.load(Ordering::Relaxed)
+    }
+
+    fn reset(&mut self) {}
+
+    fn update(&mut self, _eval: &Evaluation) {}
+
+    fn resume_from(&mut self, _snapshot: &Self) {}
+}
+
 #[cfg(test)]
 mod tests {
+    use std::{
+        sync::{
+            atomic::{AtomicBool, Ordering},
+            Arc,
+        },
+        time::Duration,
+    };
+
     use crate::annealing::stop::StopCriteria;
 
-    use super::NotGettingBetter;
+    use super::{CancelFlag, MaxEvaluations, MaxSteps, NotGettingBetter, TargetValue, TimeLimit};
+    use crate::base::Evaluation;
+
+    fn feasible(value: f64) -> Evaluation {
+        Evaluation {
+            value,
+            is_feasible: true,
+            ..Default::default()
+        }
+    }
+
+    fn infeasible(violation: f64) -> Evaluation {
+        Evaluation {
+            value: violation,
+            is_feasible: false,
+            violation,
+            ..Default::default()
+        }
+    }
 
     #[test]
     fn not_getting_better_should_stop_stops_after_max() {
@@ -128,9 +429,10 @@ mod tests {
         let mut counter = 0;
         let mut value = 0.0;
 
-        should_stop.update(value);
+        should_stop.update(&feasible(value));
         while !should_stop.should_stop() {
             value += 1.0;
+            should_stop.update(&feasible(value));
             counter += 1;
         }
 
@@ -146,8 +448,9 @@ mod tests {
         let mut counter = 0;
         let value = 0.0;
 
-        should_stop.update(value);
+        should_stop.update(&feasible(value));
         while !should_stop.should_stop() {
+            should_stop.update(&feasible(value));
             counter += 1;
         }
 
@@ -161,9 +464,10 @@ mod tests {
 
         let mut value = 0.0;
 
-        should_stop.update(value);
+        should_stop.update(&feasible(value));
         while !should_stop.should_stop() {
             value += 1.0;
+            should_stop.update(&feasible(value));
         }
 
         should_stop.reset();
@@ -184,4 +488,98 @@ mod tests {
         should_stop = NotGettingBetter::new(max, not_getting_better, true);
         assert_eq!(should_stop.best_value, f64::MAX);
     }
+
+    #[test]
+    fn any_of_stops_when_either_stops() {
+        let mut should_stop = MaxSteps::new(100).or(TargetValue::new(5.0, true));
+
+        should_stop.update(&feasible(10.0));
+        assert!(!should_stop.should_stop());
+        should_stop.update(&feasible(4.0));
+        assert!(should_stop.should_stop());
+
+        should_stop.reset();
+        assert!(!should_stop.should_stop());
+    }
+
+    #[test]
+    fn target_value_ignores_infeasible_solutions() {
+        let mut should_stop = TargetValue::new(5.0, true);
+
+        should_stop.update(&infeasible(1.0));
+        assert!(!should_stop.should_stop());
+        should_stop.update(&feasible(5.0));
+        assert!(should_stop.should_stop());
+    }
+
+    #[test]
+    fn not_getting_better_ranks_feasible_first() {
+        let mut should_stop = NotGettingBetter::new(100, 2, true);
+
+        should_stop.update(&infeasible(3.0));
+        should_stop.update(&infeasible(2.0));
+        should_stop.update(&feasible(50.0));
+        // Smaller violations are not better than a feasible solution
+        should_stop.update(&infeasible(0.5));
+        assert!(!should_stop.should_stop());
+        should_stop.update(&feasible(60.0));
+        assert!(should_stop.should_stop());
+    }
+
+    #[test]
+    fn all_of_stops_when_both_stop() {
+        let mut should_stop = MaxSteps::new(2).and(MaxEvaluations::new(10));
+
+        for _ in 0..3 {
+            should_stop.update(&feasible(0.0));
+        }
+        assert!(!should_stop.should_stop());
+        should_stop.evaluated(10);
+        assert!(should_stop.should_stop());
+    }
+
+    #[test]
+    fn time_limit_stops_after_duration() {
+        let mut should_stop = TimeLimit::new(Duration::ZERO);
+        assert!(should_stop.should_stop());
+
+        should_stop = TimeLimit::new(Duration::from_secs(3600));
+        assert!(!should_stop.should_stop());
+    }
+
+    #[test]
+    fn cancel_flag_is_shared() {
+        let flag = Arc::new(AtomicBool::new(false));
+        let should_stop = CancelFlag::new(flag.clone()).or(MaxSteps::new(100));
+        assert!(!should_stop.should_stop());
+
+        flag.store(true, Ordering::Relaxed);
+        assert!(should_stop.should_stop());
+    }
+
+    #[test]
+    fn resume_keeps_the_live_flag_and_limit() {
+        let flag = Arc::new(AtomicBool::new(false));
+        let mut snapshot = MaxSteps::new(10)
+            .or(TimeLimit::new(Duration::ZERO))
+            .or(CancelFlag::default());
+        for _ in 0..5 {
+            snapshot.update(&feasible(0.0));
+        }
+
+        let mut should_stop = MaxSteps::new(10)
+            .or(TimeLimit::new(Duration::from_secs(3600)))
+            .or(CancelFlag::new(flag.clone()));
+        should_stop.resume_from(&snapshot);
+        for _ in 0..5 {
+            should_stop.update(&feasible(0.0));
+        }
+        assert!(!should_stop.should_stop());
+        should_stop.update(&feasible(0.0));
+        assert!(should_stop.should_stop());
+
+        should_stop.reset();
+        flag.store(true, Ordering::Relaxed);
+        assert!(should_stop.should_stop());
+    }
 }
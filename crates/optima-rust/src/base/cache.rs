@@ -0,0 +1,331 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    hash::Hash,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex, MutexGuard,
+    },
+};
+
+use crate::base::criterion::Measurement;
+
+/// Lets `Criterion` hold a cache without requiring `Hash + Eq` from every solution.
+pub(crate) trait Memo<S> {
+    /// A solution cached without its objective is a miss when `needs_objective`.
+    fn get(&self, solution: &S, needs_objective: bool) -> Option<Measurement>;
+    fn insert(&self, solution: &S, measurement: Measurement);
+}
+
+/// Evaluations of the `capacity` least recently used solutions, see `Criterion::register_cache`.
+/// Solutions are told apart by `Hash` and `Eq`, which should only look at what the evaluation
+/// depends on, not at the `Evaluation` itself.
+///
+/// Entries are keyed by the solution alone. A cache used with another problem instance or other
+/// evaluation functions returns their stale evaluations, so use one cache per instance or `clear`
+/// it in between.
+pub struct EvaluationCache<S> {
+    capacity: usize,
+    entries: Mutex<Entries<S>>,
+    hits: AtomicU64,
+    misses: AtomicU64,
+    evictions: AtomicU64,
+}
+
+struct Entries<S> {
+    map: HashMap<Arc<S>, (Measurement, u64)>,
+    /// Solutions by the time they were last used, the oldest first.
+    order: BTreeMap<u64, Arc<S>>,
+    time: u64,
+}
+
+impl<S: Hash + Eq> Entries<S> {
+    /// Marks the entry of `solution` as the most recently used one.
+    fn touch(&mut self, solution: &S) -> Option<&mut Measurement> {
+        self.time += 1;
+        let key = self.map.get_key_value(solution)?.0.clone();
+        let (measurement, used) = self.map.get_mut(solution)?;
+        self.order.remove(used);
+        self.order.insert(self.time, key);
+        *used = self.time;
+        Some(measurement)
+    }
+}
+
+impl<S: Hash + Eq + Clone> EvaluationCache<S> {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            entries: Mutex::new(Entries {
+                map: HashMap::new(),
+                order: BTreeMap::new(),
+                time: 0,
+            }),
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+            evictions: AtomicU64::new(0),
+        }
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    pub fn len(&self) -> usize {
+        self.lock().map.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn hits(&self) -> u64 {
+        self.hits.load(Ordering::Relaxed)
+    }
+
+    pub fn misses(&self) -> u64 {
+        self.misses.load(Ordering::Relaxed)
+    }
+
+    /// Solutions dropped to stay within the capacity.
+    pub fn evictions(&self) -> u64 {
+        self.evictions.load(Ordering::Relaxed)
+    }
+
+    /// Share of the lookups that were hits, `None` before the first lookup.
+    pub fn hit_rate(&self) -> Option<f64> {
+        match self.hits() + self.misses() {
+            0 => None,
+            all => Some(self.hits() as f64 / all as f64),
+        }
+    }
+
+    /// Drops all solutions, e.g. after changing the constraint handling of the criterion.
+    /// The statistics are kept.
+    pub fn clear(&self) {
+        let mut entries = self.lock();
+        entries.map.clear();
+        entries.order.clear();
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Entries<S>> {
+        self.entries.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl<S: Hash + Eq + Clone> Memo<S> for EvaluationCache<S> {
+    fn get(&self, solution: &S, needs_objective: bool) -> Option<Measurement> {
+        let mut entries = self.lock();
+        let usable = entries
+            .map
+            .get(solution)
+            .is_some_and(|(measurement, _)| !needs_objective || !measurement.objective.is_nan());
+        if !usable {
+            self.misses.fetch_add(1, Ordering::Relaxed);
+            return None;
+        }
+
+        self.hits.fetch_add(1, Ordering::Relaxed);
+        entries.touch(solution).cloned()
+    }
+
+    fn insert(&self, solution: &S, measurement: Measurement) {
+        if self.capacity == 0 {
+            return;
+        }
+
+        let mut entries = self.lock();
+        // Another thread may have evaluated the same solution in the meantime
+        if let Some(old) = entries.touch(solution) {
+            *old = measurement;
+            return;
+        }
+
+        let Entries { map, order, time } = &mut *entries;
+        if map.len() >= self.capacity {
+            if let Some((_, oldest)) = order.pop_first() {
+                map.remove(&oldest);
+                self.evictions.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+
+        let key = Arc::new(solution.clone());
+        order.insert(*time, key.clone());
+        map.insert(key, (measurement, *time));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::hash::{Hash, Hasher};
+
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use super::EvaluationCache;
+    use crate::{
+        annealing::stop::MaxSteps,
+        base::{
+            solution_attr, Chromosome, ConstraintHandling, Criterion, DerivedSolution, Evaluation,
+            EvaluationCounter, OptAlgorithm, Problem, Solution,
+        },
+        genetic::{
+            operators::{
+                binary::{BitFlip, OnePointCrossover},
+                breed,
+            },
+            selection::tournament,
+            GeneticAlgorithm,
+        },
+        Result,
+    };
+
+    #[solution_attr]
+    #[derive(Clone, DerivedSolution)]
+    struct Bits {
+        genes: Vec<bool>,
+    }
+
+    impl PartialEq for Bits {
+        fn eq(&self, other: &Self) -> bool {
+            self.genes == other.genes
+        }
+    }
+
+    impl Eq for Bits {}
+
+    impl Hash for Bits {
+        fn hash<H: Hasher>(&self, state: &mut H) {
+            self.genes.hash(state);
+        }
+    }
+
+    impl Chromosome for Bits {
+        type Gene = bool;
+
+        fn genes(&self) -> &[bool] {
+            &self.genes
+        }
+
+        fn genes_mut(&mut self) -> &mut [bool] {
+            &mut self.genes
+        }
+    }
+
+    fn bits(n: usize) -> Bits {
+        Bits {
+            genes: (0..8).map(|i| n & (1 << i) != 0).collect(),
+            eval: Evaluation::default(),
+        }
+    }
+
+    #[derive(Clone, Copy)]
+    struct TestProblem;
+    impl Problem for TestProblem {}
+
+    fn penalty(_: &TestProblem, solution: &Bits) -> f64 {
+        f64::max(
+            solution.genes.iter().filter(|g| **g).count() as f64 - 6.0,
+            0.0,
+        )
+    }
+
+    fn value(_: &TestProblem, solution: &Bits) -> f64 {
+        let weights = solution.genes.iter().enumerate();
+        weights.filter(|(_, g)| **g).map(|(i, _)| i as f64).sum()
+    }
+
+    #[test]
+    fn evicts_least_recently_used() {
+        let cache = EvaluationCache::new(2);
+        let counter = EvaluationCounter::new();
+        let mut criterion = Criterion::new(&penalty, &value, false);
+        criterion.register_cache(&cache);
+        criterion.register_counter(&counter);
+
+        let values: Vec<f64> = [1, 2, 1, 3, 2, 1]
+            .into_iter()
+            .map(|n| {
+                let mut solution = bits(n);
+                criterion.evaluate(&TestProblem, &mut solution);
+                solution.get_value()
+            })
+            .collect();
+
+        assert_eq!(vec![0.0, 1.0, 0.0, 1.0, 1.0, 0.0], values);
+        assert_eq!((1, 5, 3), (cache.hits(), cache.misses(), cache.evictions()));
+        assert_eq!(5, counter.evaluations());
+        assert_eq!(2, cache.len());
+        assert_eq!(Some(1.0 / 6.0), cache.hit_rate());
+    }
+
+    #[test]
+    fn entries_without_objective_are_misses_for_penalties() {
+        let cache = EvaluationCache::new(2);
+        let counter = EvaluationCounter::new();
+        let mut criterion = Criterion::new(&penalty, &value, false);
+        criterion.register_cache(&cache);
+        criterion.register_counter(&counter);
+
+        // Every bit is set, violating the limit of 6 by 2
+        let mut solution = bits(255);
+        criterion.evaluate(&TestProblem, &mut solution);
+        assert_eq!(2.0, solution.get_value());
+
+        criterion.set_constraint_handling(ConstraintHandling::StaticPenalty { weight: 10.0 });
+        criterion.evaluate(&TestProblem, &mut solution);
+        criterion.evaluate(&TestProblem, &mut solution);
+
+        assert_eq!(28.0 - 20.0, solution.get_value());
+        assert_eq!((1, 2), (cache.hits(), cache.misses()));
+        assert_eq!(2, counter.evaluations());
+    }
+
+    #[test]
+    fn genetic_algorithm_reuses_evaluations() {
+        let change = |population: &mut Vec<Bits>, rng: &mut StdRng| {
+            breed(
+                population,
+                &OnePointCrossover,
+                0.5,
+                &BitFlip::new(0.02),
+                rng,
+            )
+        };
+
+        fn select(_: usize, population: &Vec<Bits>, rng: &mut StdRng) -> Result<Vec<Bits>> {
+            tournament(
+                3,
+                population,
+                &Criterion::new(&penalty, &value, false),
+                rng,
+                0,
+            )
+        }
+
+        let solve = |cache: Option<&EvaluationCache<Bits>>| {
+            let mut criterion = Criterion::new(&penalty, &value, false);
+            if let Some(cache) = cache {
+                criterion.register_cache(cache);
+            }
+            let population = (0..20).map(|i| bits(i * 7)).collect();
+            let mut genetic = GeneticAlgorithm::with_rng(
+                20,
+                population,
+                &change,
+                &select,
+                MaxSteps::new(30),
+                None,
+                StdRng::seed_from_u64(25),
+            );
+            let best = genetic
+                .solve(TestProblem, &mut criterion)
+                .unwrap()
+                .remove(0);
+            (best.genes, best.eval.value)
+        };
+
+        let cache = EvaluationCache::new(100);
+        assert_eq!(solve(None), solve(Some(&cache)));
+        assert!(cache.hits() > cache.misses());
+        assert!(cache.len() <= 100);
+    }
+}
@@ -0,0 +1,36 @@
+/// How `Criterion` treats solutions violating the constraints, the violation of a solution is the
+/// absolute value of its penalty. Strategies changing over time follow the iterations reported by
+/// the algorithms with `Criterion::next_iteration`.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ConstraintHandling {
+    /// Feasible solutions are better than infeasible ones, which are compared by violation.
+    /// The value of an infeasible solution is its violation.
+    #[default]
+    FeasibilityFirst,
+    /// The value is the objective worsened by `weight * violation`.
+    StaticPenalty { weight: f64 },
+    /// Joines and Houck, the value is the objective worsened by `(c * t)^alpha * violation^beta`
+    /// in iteration `t` counted from 1.
+    DynamicPenalty { c: f64, alpha: f64, beta: f64 },
+    /// Bean and Hadj-Alouane, like `StaticPenalty` but the weight starts at `initial` and is
+    /// divided by `decrease` once the reported solution was feasible for `window` iterations in a
+    /// row, or multiplied by `increase` once it was infeasible for `window` iterations.
+    AdaptivePenalty {
+        initial: f64,
+        increase: f64,
+        decrease: f64,
+        window: u32,
+    },
+    /// Runarsson and Yao, two solutions are compared by objective when both are feasible or with
+    /// `probability`, by violation otherwise.
+    StochasticRanking { probability: f64 },
+    /// Takahama and Sakai, violations up to epsilon count as none and solutions with the same
+    /// violation are compared by objective. Epsilon falls from `initial` in the first iteration
+    /// to 0 after `iterations` as `initial * (1 - t / iterations)^exponent`.
+    EpsilonConstrained {
+        initial: f64,
+        iterations: u32,
+        exponent: f64,
+    },
+}
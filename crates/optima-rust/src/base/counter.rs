@@ -0,0 +1,90 @@
+use std::{
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Mutex,
+    },
+    time::{Duration, Instant},
+};
+
+use crate::base::Evaluation;
+
+/// Counts the evaluations done through a `Criterion`, see `Criterion::register_counter`.
+/// With a target it also notes when a feasible solution first reached it.
+pub struct EvaluationCounter {
+    start: Instant,
+    target: Option<f64>,
+    evaluations: AtomicU64,
+    incremental: AtomicU64,
+    hit: Mutex<Option<(Duration, u64)>>,
+}
+
+impl EvaluationCounter {
+    pub fn new() -> Self {
+        Self {
+            start: Instant::now(),
+            target: None,
+            evaluations: AtomicU64::new(0),
+            incremental: AtomicU64::new(0),
+            hit: Mutex::new(None),
+        }
+    }
+
+    pub fn with_target(target: f64) -> Self {
+        Self {
+            target: Some(target),
+            ..Self::new()
+        }
+    }
+
+    /// All evaluations, including the incremental ones.
+    pub fn evaluations(&self) -> u64 {
+        self.evaluations.load(Ordering::Relaxed)
+    }
+
+    /// Evaluations done from a move delta by `Criterion::evaluate_move`.
+    pub fn incremental(&self) -> u64 {
+        self.incremental.load(Ordering::Relaxed)
+    }
+
+    /// Time since the counter was created.
+    pub fn elapsed(&self) -> Duration {
+        self.start.elapsed()
+    }
+
+    pub fn time_to_target(&self) -> Option<Duration> {
+        self.first_hit().map(|(time, _)| time)
+    }
+
+    pub fn evaluations_to_target(&self) -> Option<u64> {
+        self.first_hit().map(|(_, evaluations)| evaluations)
+    }
+
+    pub(crate) fn record(&self, eval: &Evaluation, is_minimization: bool, incremental: bool) {
+        let evaluations = self.evaluations.fetch_add(1, Ordering::Relaxed) + 1;
+        if incremental {
+            self.incremental.fetch_add(1, Ordering::Relaxed);
+        }
+
+        let reached = match (self.target, eval.is_feasible) {
+            (Some(target), true) if is_minimization => eval.value <= target,
+            (Some(target), true) => eval.value >= target,
+            _ => false,
+        };
+        if reached {
+            let mut hit = self.hit.lock().unwrap_or_else(|e| e.into_inner());
+            if hit.is_none() {
+                *hit = Some((self.start.elapsed(), evaluations));
+            }
+        }
+    }
+
+    fn first_hit(&self) -> Option<(Duration, u64)> {
+        *self.hit.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl Default for EvaluationCounter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -1,8 +1,40 @@
-use crate::base::{Evaluation, Problem, Solution};
+use std::{
+    cmp::Ordering,
+    hash::{DefaultHasher, Hash, Hasher},
+};
+
+use crate::{
+    base::{
+        cache::Memo, ConstraintHandling, DeltaMove, Evaluation, EvaluationCache, EvaluationCounter,
+        Problem, Solution,
+    },
+    Error, Result,
+};
+
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+pub type EvaluationFn<S, P> = dyn Fn(&P, &S) -> f64 + Send + Sync;
+
+/// Everything an evaluation is made of before the constraint handling turns it into a value.
+#[derive(Clone, Debug)]
+pub(crate) struct Measurement {
+    pub(super) objective: f64,
+    violation: f64,
+    violations: Vec<f64>,
+}
 
-pub type EvaluationFn<S, P> = dyn Fn(&P, &S) -> f64;
+/// Progress of the constraint handling strategy, saved in checkpoints so a resumed run weighs
+/// constraints the same as the run it was taken from.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CriterionState {
+    iteration: u32,
+    weight: f64,
+    streak: i32,
+}
 
-#[derive(Clone, Copy)]
+#[derive(Clone)]
 pub struct Criterion<'a, P, S>
 where
     S: Solution,
@@ -10,6 +42,17 @@ where
     penalty: &'a EvaluationFn<S, P>,
     value: &'a EvaluationFn<S, P>,
     pub is_minimization: bool,
+    /// Makes `evaluate_move` verify every incremental update against a full evaluation.
+    pub check_delta: bool,
+    counter: Option<&'a EvaluationCounter>,
+    cache: Option<&'a (dyn Memo<S> + Sync)>,
+    constraints: Vec<(String, &'a EvaluationFn<S, P>)>,
+    handling: ConstraintHandling,
+    iteration: u32,
+    /// Current weight of `ConstraintHandling::AdaptivePenalty`.
+    weight: f64,
+    /// Iterations in a row with a feasible (positive) or an infeasible (negative) solution.
+    streak: i32,
 }
 
 impl<'a, P, S> Criterion<'a, P, S>
@@ -26,40 +69,370 @@ where
             penalty,
             value,
             is_minimization,
+            check_delta: false,
+            counter: None,
+            cache: None,
+            constraints: vec![],
+            handling: ConstraintHandling::FeasibilityFirst,
+            iteration: 0,
+            weight: 0.0,
+            streak: 0,
         }
     }
 
-    pub fn is_first_better(&self, first: &Evaluation, second: &Evaluation) -> bool {
-        if first.is_feasible && !second.is_feasible {
-            return true;
+    /// Adds a constraint returning how much a solution violates it, the violation of a solution
+    /// is the sum of the absolute values of the penalty and of all constraints.
+    pub fn register_constraint(&mut self, name: &str, constraint: &'a EvaluationFn<S, P>) {
+        self.constraints.push((name.to_string(), constraint));
+    }
+
+    pub fn constraint_names(&self) -> Vec<&str> {
+        self.constraints
+            .iter()
+            .map(|(name, _)| name.as_str())
+            .collect()
+    }
+
+    /// Registered constraints `eval` violates, with the amount.
+    pub fn violated<'e>(&'e self, eval: &Evaluation) -> Vec<(&'e str, f64)> {
+        self.constraints
+            .iter()
+            .zip(&eval.violations)
+            .filter(|(_, violation)| **violation != 0.0)
+            .map(|((name, _), violation)| (name.as_str(), *violation))
+            .collect()
+    }
+
+    /// Restarts the strategy, values of solutions evaluated before are not updated.
+    pub fn set_constraint_handling(&mut self, handling: ConstraintHandling) {
+        self.handling = handling;
+        self.iteration = 0;
+        self.streak = 0;
+        self.weight = match handling {
+            ConstraintHandling::AdaptivePenalty { initial, .. } => initial,
+            _ => 0.0,
         };
+    }
+
+    pub fn constraint_handling(&self) -> ConstraintHandling {
+        self.handling
+    }
+
+    pub fn state(&self) -> CriterionState {
+        CriterionState {
+            iteration: self.iteration,
+            weight: self.weight,
+            streak: self.streak,
+        }
+    }
+
+    /// Continues the strategy from `state`, taken by `state` of a criterion with the same
+    /// constraint handling.
+    pub fn restore(&mut self, state: CriterionState) {
+        self.iteration = state.iteration;
+        self.weight = state.weight;
+        self.streak = state.streak;
+    }
 
-        if !first.is_feasible && second.is_feasible {
-            return false;
+    /// Called by the algorithms after every iteration with the solution the search continues
+    /// from, the best of the population for population based algorithms.
+    pub fn next_iteration(&mut self, current: &Evaluation) {
+        self.iteration += 1;
+
+        if let ConstraintHandling::AdaptivePenalty {
+            increase,
+            decrease,
+            window,
+            ..
+        } = self.handling
+        {
+            self.streak = match current.is_feasible {
+                true => self.streak.max(0) + 1,
+                false => self.streak.min(0) - 1,
+            };
+
+            if self.streak >= window as i32 {
+                self.weight /= decrease;
+                self.streak = 0;
+            } else if -self.streak >= window as i32 {
+                self.weight *= increase;
+                self.streak = 0;
+            }
+        }
+    }
+
+    /// Violation still treated as none by `ConstraintHandling::EpsilonConstrained`, 0 for the
+    /// other strategies.
+    pub fn epsilon(&self) -> f64 {
+        match self.handling {
+            ConstraintHandling::EpsilonConstrained {
+                initial,
+                iterations,
+                exponent,
+            } if self.iteration < iterations => {
+                initial * (1.0 - self.iteration as f64 / iterations as f64).powf(exponent)
+            }
+            _ => 0.0,
         }
+    }
+
+    /// Makes `counter` record every evaluation done through this criterion and its copies.
+    pub fn register_counter(&mut self, counter: &'a EvaluationCounter) {
+        self.counter = Some(counter);
+    }
 
-        // Lower penalty
-        if !second.is_feasible {
-            return first.value < second.value;
+    /// Makes `evaluate` reuse the evaluations of solutions already in `cache`, cache hits are not
+    /// counted as evaluations. Moves evaluated from a delta bypass the cache.
+    pub fn register_cache(&mut self, cache: &'a EvaluationCache<S>)
+    where
+        S: Hash + Eq + Send + Sync,
+    {
+        self.cache = Some(cache);
+    }
+
+    pub fn is_first_better(&self, first: &Evaluation, second: &Evaluation) -> bool {
+        match self.handling {
+            ConstraintHandling::FeasibilityFirst => {
+                if first.is_feasible != second.is_feasible {
+                    return first.is_feasible;
+                }
+
+                // Lower violation
+                if !second.is_feasible {
+                    return first.value < second.value;
+                }
+            }
+            ConstraintHandling::StochasticRanking { probability } => {
+                let by_value = (first.is_feasible && second.is_feasible)
+                    || self.draw(first, second) < probability;
+                if !by_value {
+                    return first.violation < second.violation;
+                }
+            }
+            ConstraintHandling::EpsilonConstrained { .. } => {
+                let epsilon = self.epsilon();
+                let level = |eval: &Evaluation| match eval.violation <= epsilon {
+                    true => 0.0,
+                    false => eval.violation,
+                };
+                if level(first) != level(second) {
+                    return level(first) < level(second);
+                }
+            }
+            ConstraintHandling::StaticPenalty { .. }
+            | ConstraintHandling::DynamicPenalty { .. }
+            | ConstraintHandling::AdaptivePenalty { .. } => {}
         }
 
         // Compare value according to problem type
-        return match self.is_minimization {
+        match self.is_minimization {
             true => first.value < second.value,
             false => first.value > second.value,
-        };
+        }
+    }
+
+    /// Size of the difference between two evaluations in the units they are compared in, the
+    /// violation when feasibility first compares an infeasible solution and the value otherwise.
+    pub fn difference(&self, first: &Evaluation, second: &Evaluation) -> f64 {
+        let by_violation = self.handling == ConstraintHandling::FeasibilityFirst
+            && !(first.is_feasible && second.is_feasible);
+        match by_violation {
+            true => (first.violation - second.violation).abs(),
+            false => (first.value - second.value).abs(),
+        }
+    }
+
+    /// Number in [0, 1) that is the same for both orders of the pair within an iteration,
+    /// so that stochastic ranking can't prefer both solutions at once.
+    fn draw(&self, first: &Evaluation, second: &Evaluation) -> f64 {
+        let key = |eval: &Evaluation| (eval.value.to_bits(), eval.violation.to_bits());
+        let (first, second) = (key(first), key(second));
+
+        let mut hasher = DefaultHasher::new();
+        (first.min(second), first.max(second), self.iteration).hash(&mut hasher);
+        (hasher.finish() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    /// Orders evaluations from the best to the worst. NaN values are not ordered,
+    /// use `sort` for whole populations.
+    pub fn compare(&self, first: &Evaluation, second: &Evaluation) -> Ordering {
+        if self.is_first_better(first, second) {
+            Ordering::Less
+        } else if self.is_first_better(second, first) {
+            Ordering::Greater
+        } else {
+            Ordering::Equal
+        }
+    }
+
+    /// Sorts the population from the best to the worst specimen, fails on NaN values.
+    pub fn sort(&self, population: &mut [S]) -> Result<()> {
+        if population.iter().any(|specimen| specimen.get_value().is_nan()) {
+            return Err(Error::NotANumber);
+        }
+
+        // Random comparisons aren't a total order, sort them the way Runarsson and Yao do
+        if let ConstraintHandling::StochasticRanking { .. } = self.handling {
+            for _ in 0..population.len() {
+                let mut swapped = false;
+                for j in 1..population.len() {
+                    if self.is_first_better(population[j].get_eval(), population[j - 1].get_eval())
+                    {
+                        population.swap(j - 1, j);
+                        swapped = true;
+                    }
+                }
+                if !swapped {
+                    break;
+                }
+            }
+            return Ok(());
+        }
+
+        population.sort_by(|a, b| self.compare(a.get_eval(), b.get_eval()));
+        Ok(())
     }
 
     pub fn evaluate(&self, problem: &P, solution: &mut S) {
-        let mut value = (self.penalty)(problem, solution);
-        let is_feasible = value == 0.0;
-        if is_feasible {
-            value = (self.value)(problem, solution);
+        // Feasibility first leaves out the objective of infeasible solutions, others need it
+        let needs_objective = self.handling != ConstraintHandling::FeasibilityFirst;
+        if let Some(cache) = self.cache {
+            if let Some(measurement) = cache.get(solution, needs_objective) {
+                return self.assign(solution.get_eval_mut(), measurement);
+            }
+        }
+
+        let measurement = self.measure(problem, solution);
+        if let Some(cache) = self.cache {
+            cache.insert(solution, measurement.clone());
+        }
+        self.assign(solution.get_eval_mut(), measurement);
+        self.record(solution, false);
+    }
+
+    fn measure(&self, problem: &P, solution: &S) -> Measurement {
+        let violations = self.violations(problem, solution);
+        let violation = (self.penalty)(problem, solution).abs() + violations.iter().sum::<f64>();
+        // Feasibility first never looks at the objective of infeasible solutions
+        let objective =
+            match violation == 0.0 || self.handling != ConstraintHandling::FeasibilityFirst {
+                true => (self.value)(problem, solution),
+                false => f64::NAN,
+            };
+
+        Measurement {
+            objective,
+            violation,
+            violations,
+        }
+    }
+
+    fn violations(&self, problem: &P, solution: &S) -> Vec<f64> {
+        self.constraints
+            .iter()
+            .map(|(_, constraint)| constraint(problem, solution).abs())
+            .collect()
+    }
+
+    fn assign(&self, eval: &mut Evaluation, measurement: Measurement) {
+        let Measurement {
+            objective,
+            violation,
+            violations,
+        } = measurement;
+        eval.violation = violation;
+        eval.violations = violations;
+        eval.is_feasible = violation == 0.0;
+
+        let weighted = match self.handling {
+            ConstraintHandling::FeasibilityFirst if violation > 0.0 => {
+                eval.value = violation;
+                return;
+            }
+            ConstraintHandling::StaticPenalty { weight } => weight * violation,
+            ConstraintHandling::DynamicPenalty { c, alpha, beta } => {
+                (c * (self.iteration + 1) as f64).powf(alpha) * violation.powf(beta)
+            }
+            ConstraintHandling::AdaptivePenalty { .. } => self.weight * violation,
+            _ => 0.0,
+        };
+
+        eval.value = match self.is_minimization {
+            true => objective + weighted,
+            false => objective - weighted,
+        };
+    }
+
+    fn record(&self, solution: &S, incremental: bool) {
+        if let Some(counter) = self.counter {
+            counter.record(solution.get_eval(), self.is_minimization, incremental);
+        }
+    }
+
+    /// Evaluates `solution`, produced from the evaluated `before` by `change`, by adding the move's
+    /// delta to the evaluation of `before`. Falls back to `evaluate` when the move has no delta or
+    /// `before` is infeasible (its value may not be the objective then). Registered constraints are
+    /// always evaluated in full.
+    ///
+    /// Panics when `check_delta` is set and the result differs from a full evaluation.
+    pub fn evaluate_move<M>(&self, problem: &P, before: &S, solution: &mut S, change: &M)
+    where
+        M: DeltaMove<P, S>,
+    {
+        let delta = match before.get_eval().is_feasible {
+            true => change.delta(problem, before),
+            false => None,
+        };
+        let delta = match delta {
+            Some(delta) => delta,
+            None => return self.evaluate(problem, solution),
+        };
+
+        // `before` is feasible, so its penalty is 0 and the change is the new penalty
+        let violations = self.violations(problem, solution);
+        let measurement = Measurement {
+            objective: before.get_value() + delta.value,
+            violation: delta.penalty.abs() + violations.iter().sum::<f64>(),
+            violations,
+        };
+        self.assign(solution.get_eval_mut(), measurement);
+        self.record(solution, true);
+
+        if self.check_delta {
+            let mut full = solution.clone();
+            let measurement = self.measure(problem, &full);
+            self.assign(full.get_eval_mut(), measurement);
+
+            let (incremental, full) = (solution.get_eval(), full.get_eval());
+            let tolerance = 1e-9 * f64::max(1.0, full.value.abs());
+            assert!(
+                incremental.is_feasible == full.is_feasible
+                    && (incremental.value - full.value).abs() <= tolerance,
+                "delta evaluation gave {} (feasible: {}), full evaluation {} (feasible: {})",
+                incremental.value,
+                incremental.is_feasible,
+                full.value,
+                full.is_feasible
+            );
+        }
+    }
+
+    pub fn evaluate_all(&self, problem: &P, population: &mut [S]) {
+        for specimen in population.iter_mut() {
+            self.evaluate(problem, specimen);
         }
+    }
 
-        let mut eval = solution.get_eval_mut();
-        eval.value = value;
-        eval.is_feasible = is_feasible;
+    /// Evaluates the whole population on the rayon thread pool.
+    #[cfg(feature = "parallel")]
+    pub fn par_evaluate_all(&self, problem: &P, population: &mut [S])
+    where
+        P: Sync,
+        S: Send,
+    {
+        population
+            .par_iter_mut()
+            .for_each(|specimen| self.evaluate(problem, specimen));
     }
 }
 
@@ -67,9 +440,10 @@ where
 mod tests {
     use optima_macros::{solution_attr, DerivedSolution};
 
-    use crate::base::{Evaluation, Problem, Solution};
+    use crate::base::{Delta, DeltaMove, Evaluation, Problem, Solution};
 
     use super::Criterion;
+    use crate::base::ConstraintHandling;
 
     #[solution_attr]
     #[derive(Clone, DerivedSolution)]
@@ -144,6 +518,7 @@ mod tests {
             eval: Evaluation {
                 value: 10.0,
                 is_feasible: false,
+                ..Default::default()
             },
         };
 
@@ -168,10 +543,12 @@ mod tests {
         let mut info_a = Evaluation {
             value: 10.0,
             is_feasible: true,
+            ..Default::default()
         };
         let mut info_b = Evaluation {
             value: 20.0,
             is_feasible: true,
+            ..Default::default()
         };
 
         assert_eq!(false, criterion.is_first_better(&info_a, &info_b));
@@ -201,15 +578,323 @@ mod tests {
         let info_a = Evaluation {
             value: 30.0,
             is_feasible: true,
+            ..Default::default()
         };
 
         let info_b = Evaluation {
             value: 2.0,
             is_feasible: false,
+            ..Default::default()
         };
 
         assert_eq!(true, criterion.is_first_better(&info_a, &info_b));
         criterion.is_minimization = true;
         assert_eq!(true, criterion.is_first_better(&info_a, &info_b));
     }
+
+    #[test]
+    fn sort_orders_best_first_and_rejects_nan() {
+        fn penalty(_: &TestProblem, _: &TestSolution) -> f64 {
+            0.0
+        }
+
+        fn value(_: &TestProblem, _: &TestSolution) -> f64 {
+            20.0
+        }
+        let criterion = Criterion::<TestProblem, TestSolution>::new(&penalty, &value, true);
+        let mut population = vec![TestSolution::default(); 3];
+        for (specimen, value) in population.iter_mut().zip([3.0, 1.0, 2.0]) {
+            specimen.eval.value = value;
+            specimen.eval.is_feasible = true;
+        }
+
+        criterion.sort(&mut population).unwrap();
+        let values: Vec<f64> = population.iter().map(|s| s.get_value()).collect();
+        assert_eq!(vec![1.0, 2.0, 3.0], values);
+
+        population[1].eval.value = f64::NAN;
+        assert!(matches!(
+            criterion.sort(&mut population),
+            Err(crate::Error::NotANumber)
+        ));
+    }
+
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn par_evaluate_all_evaluates_every_specimen() {
+        fn penalty(_: &TestProblem, _: &TestSolution) -> f64 {
+            0.0
+        }
+
+        fn value(_: &TestProblem, _: &TestSolution) -> f64 {
+            20.0
+        }
+        let criterion = Criterion::<TestProblem, TestSolution>::new(&penalty, &value, false);
+        let mut population = vec![TestSolution::default(); 100];
+
+        criterion.par_evaluate_all(&TestProblem {}, &mut population);
+
+        for specimen in population {
+            assert_eq!(20.0, specimen.get_value());
+            assert!(specimen.get_eval().is_feasible);
+        }
+    }
+
+    fn overweight(_: &TestProblem, s: &Position) -> f64 {
+        f64::min(10.0 - s.x, 0.0)
+    }
+
+    fn coordinate(_: &TestProblem, s: &Position) -> f64 {
+        s.x
+    }
+
+    fn evaluated(criterion: &Criterion<TestProblem, Position>, xs: &[f64]) -> Vec<Position> {
+        xs.iter()
+            .map(|x| {
+                let mut position = Position {
+                    x: *x,
+                    eval: Evaluation::default(),
+                };
+                criterion.evaluate(&TestProblem, &mut position);
+                position
+            })
+            .collect()
+    }
+
+    fn xs(population: &[Position]) -> Vec<f64> {
+        population.iter().map(|p| p.x).collect()
+    }
+
+    #[test]
+    fn feasibility_first_handles_negative_penalty() {
+        let criterion = Criterion::new(&overweight, &coordinate, false);
+        let mut population = evaluated(&criterion, &[15.0, 8.0, 12.0]);
+
+        assert_eq!(5.0, population[0].get_value());
+        criterion.sort(&mut population).unwrap();
+        assert_eq!(vec![8.0, 12.0, 15.0], xs(&population));
+    }
+
+    #[test]
+    fn difference_is_in_the_compared_units() {
+        let mut criterion = Criterion::new(&overweight, &coordinate, false);
+        let population = evaluated(&criterion, &[4.0, 9.0, 12.0, 15.0]);
+        let eval = |i: usize| population[i].get_eval();
+        assert_eq!(5.0, criterion.difference(eval(0), eval(1)));
+        assert_eq!(2.0, criterion.difference(eval(1), eval(2)));
+        assert_eq!(3.0, criterion.difference(eval(2), eval(3)));
+
+        criterion.set_constraint_handling(ConstraintHandling::StaticPenalty { weight: 3.0 });
+        let population = evaluated(&criterion, &[9.0, 12.0]);
+        assert_eq!(
+            3.0,
+            criterion.difference(population[0].get_eval(), population[1].get_eval())
+        );
+    }
+
+    #[test]
+    fn penalties_worsen_the_objective() {
+        let mut criterion = Criterion::new(&overweight, &coordinate, false);
+        criterion.set_constraint_handling(ConstraintHandling::StaticPenalty { weight: 3.0 });
+        let population = evaluated(&criterion, &[9.0, 12.0]);
+        assert_eq!(vec![9.0, 6.0], values(&population));
+        assert_eq!(2.0, population[1].get_eval().violation);
+
+        criterion.set_constraint_handling(ConstraintHandling::DynamicPenalty {
+            c: 0.5,
+            alpha: 2.0,
+            beta: 1.0,
+        });
+        criterion.next_iteration(population[0].get_eval());
+        criterion.next_iteration(population[0].get_eval());
+        assert_eq!(vec![9.0, 7.5], values(&evaluated(&criterion, &[9.0, 12.0])));
+    }
+
+    #[test]
+    fn adaptive_penalty_follows_feasibility() {
+        let mut criterion = Criterion::new(&overweight, &coordinate, true);
+        criterion.set_constraint_handling(ConstraintHandling::AdaptivePenalty {
+            initial: 1.0,
+            increase: 4.0,
+            decrease: 2.0,
+            window: 2,
+        });
+        let population = evaluated(&criterion, &[5.0, 11.0]);
+        assert_eq!(12.0, population[1].get_value());
+
+        let (feasible, infeasible) = (population[0].get_eval(), population[1].get_eval());
+        for eval in [infeasible, infeasible, feasible, infeasible, infeasible] {
+            criterion.next_iteration(eval);
+        }
+        assert_eq!(27.0, evaluated(&criterion, &[11.0])[0].get_value());
+
+        for _ in 0..4 {
+            criterion.next_iteration(feasible);
+        }
+        assert_eq!(15.0, evaluated(&criterion, &[11.0])[0].get_value());
+    }
+
+    #[test]
+    fn epsilon_shrinks_to_zero() {
+        let mut criterion = Criterion::new(&overweight, &coordinate, false);
+        criterion.set_constraint_handling(ConstraintHandling::EpsilonConstrained {
+            initial: 4.0,
+            iterations: 4,
+            exponent: 1.0,
+        });
+        let mut population = evaluated(&criterion, &[9.0, 13.0, 11.0, 15.0]);
+
+        criterion.sort(&mut population).unwrap();
+        assert_eq!(vec![13.0, 11.0, 9.0, 15.0], xs(&population));
+
+        for _ in 0..2 {
+            criterion.next_iteration(population[0].get_eval());
+        }
+        assert_eq!(2.0, criterion.epsilon());
+        criterion.sort(&mut population).unwrap();
+        assert_eq!(vec![11.0, 9.0, 13.0, 15.0], xs(&population));
+
+        for _ in 0..2 {
+            criterion.next_iteration(population[0].get_eval());
+        }
+        assert_eq!(0.0, criterion.epsilon());
+        criterion.sort(&mut population).unwrap();
+        assert_eq!(vec![9.0, 11.0, 13.0, 15.0], xs(&population));
+    }
+
+    #[test]
+    fn stochastic_ranking_mixes_objective_and_violation() {
+        let mut criterion = Criterion::new(&overweight, &coordinate, false);
+        let ranked = |criterion: &mut Criterion<TestProblem, Position>, probability: f64| {
+            criterion
+                .set_constraint_handling(ConstraintHandling::StochasticRanking { probability });
+            let mut population = evaluated(criterion, &[9.0, 13.0, 11.0, 8.0, 15.0]);
+            criterion.sort(&mut population).unwrap();
+            xs(&population)
+        };
+
+        assert_eq!(
+            vec![15.0, 13.0, 11.0, 9.0, 8.0],
+            ranked(&mut criterion, 1.0)
+        );
+        assert_eq!(
+            vec![9.0, 8.0, 11.0, 13.0, 15.0],
+            ranked(&mut criterion, 0.0)
+        );
+
+        let mixed = ranked(&mut criterion, 0.45);
+        let mut sorted = mixed.clone();
+        sorted.sort_by(f64::total_cmp);
+        assert_eq!(vec![8.0, 9.0, 11.0, 13.0, 15.0], sorted);
+    }
+
+    #[test]
+    fn named_constraints_add_up() {
+        let below = |_: &TestProblem, s: &Position| f64::max(s.x - 4.0, 0.0);
+        let odd = |_: &TestProblem, s: &Position| s.x % 2.0 - 1.0;
+        let mut criterion = Criterion::new(&overweight, &coordinate, false);
+        criterion.register_constraint("below", &below);
+        criterion.register_constraint("odd", &odd);
+        criterion.check_delta = true;
+
+        let mut population = evaluated(&criterion, &[3.0, 6.0, 12.0]);
+        assert_eq!(vec!["below", "odd"], criterion.constraint_names());
+        assert!(population[0].get_eval().is_feasible);
+        assert_eq!(vec![2.0, 1.0], population[1].get_eval().violations);
+        assert_eq!(11.0, population[2].get_value());
+        assert_eq!(
+            vec![("below", 8.0), ("odd", 1.0)],
+            criterion.violated(population[2].get_eval())
+        );
+
+        let moved = step(&criterion, &population[0], 2.0);
+        assert_eq!(vec![("below", 1.0)], criterion.violated(moved.get_eval()));
+
+        criterion.sort(&mut population).unwrap();
+        assert_eq!(vec![3.0, 6.0, 12.0], xs(&population));
+    }
+
+    fn values(population: &[Position]) -> Vec<f64> {
+        population.iter().map(|p| p.get_value()).collect()
+    }
+
+    #[solution_attr]
+    #[derive(Clone, DerivedSolution)]
+    struct Position {
+        x: f64,
+    }
+
+    // Moves the position by the given step, positions above 10 are infeasible
+    struct Step(f64);
+
+    impl DeltaMove<TestProblem, Position> for Step {
+        fn delta(&self, _: &TestProblem, before: &Position) -> Option<Delta> {
+            Some(Delta {
+                penalty: f64::max(before.x + self.0 - 10.0, 0.0) - f64::max(before.x - 10.0, 0.0),
+                value: self.0,
+            })
+        }
+    }
+
+    fn step(
+        criterion: &Criterion<TestProblem, Position>,
+        before: &Position,
+        change: f64,
+    ) -> Position {
+        let mut after = before.clone();
+        after.x += change;
+        criterion.evaluate_move(&TestProblem, before, &mut after, &Step(change));
+        after
+    }
+
+    #[test]
+    fn evaluate_move_matches_full_evaluation() {
+        fn penalty(_: &TestProblem, s: &Position) -> f64 {
+            f64::max(s.x - 10.0, 0.0)
+        }
+
+        fn value(_: &TestProblem, s: &Position) -> f64 {
+            s.x
+        }
+        let mut criterion = Criterion::new(&penalty, &value, false);
+        criterion.check_delta = true;
+        let mut position = Position {
+            x: 2.0,
+            eval: Evaluation::default(),
+        };
+        criterion.evaluate(&TestProblem, &mut position);
+
+        let position = step(&criterion, &position, 3.0);
+        assert_eq!(5.0, position.get_value());
+
+        let position = step(&criterion, &position, 7.0);
+        assert_eq!(2.0, position.get_value());
+        assert!(!position.get_eval().is_feasible);
+
+        // Infeasible solutions are evaluated from scratch
+        let position = step(&criterion, &position, -4.0);
+        assert_eq!(8.0, position.get_value());
+        assert!(position.get_eval().is_feasible);
+    }
+
+    #[test]
+    #[should_panic(expected = "delta evaluation")]
+    fn check_delta_catches_wrong_delta() {
+        fn penalty(_: &TestProblem, _: &Position) -> f64 {
+            0.0
+        }
+
+        fn value(_: &TestProblem, s: &Position) -> f64 {
+            2.0 * s.x
+        }
+        let mut criterion = Criterion::new(&penalty, &value, false);
+        criterion.check_delta = true;
+        let mut position = Position {
+            x: 1.0,
+            eval: Evaluation::default(),
+        };
+        criterion.evaluate(&TestProblem, &mut position);
+
+        step(&criterion, &position, 1.0);
+    }
 }
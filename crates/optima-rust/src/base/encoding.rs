@@ -0,0 +1,156 @@
+use std::ops::RangeInclusive;
+
+use rand::Rng;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GeneKind {
+    Real,
+    Integer,
+    Boolean,
+}
+
+/// One encoded field of an `Encoding`, booleans are encoded as `0.0` and `1.0`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Gene {
+    pub name: &'static str,
+    pub kind: GeneKind,
+    pub range: RangeInclusive<f64>,
+}
+
+impl Gene {
+    pub fn real(name: &'static str, range: RangeInclusive<f64>) -> Self {
+        Self {
+            name,
+            kind: GeneKind::Real,
+            range,
+        }
+    }
+
+    pub fn integer(name: &'static str, range: RangeInclusive<f64>) -> Self {
+        Self {
+            name,
+            kind: GeneKind::Integer,
+            range,
+        }
+    }
+
+    pub fn boolean(name: &'static str) -> Self {
+        Self {
+            name,
+            kind: GeneKind::Boolean,
+            range: 0.0..=1.0,
+        }
+    }
+
+    /// Clamps `value` into the range, rounding it for integer and boolean genes.
+    pub fn decode(&self, value: f64) -> f64 {
+        let value = match self.kind {
+            GeneKind::Real => value,
+            GeneKind::Integer | GeneKind::Boolean => value.round(),
+        };
+        value.clamp(*self.range.start(), *self.range.end())
+    }
+
+    /// Uniformly distributed value of the gene.
+    pub fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> f64 {
+        let (start, end) = (*self.range.start(), *self.range.end());
+        match self.kind {
+            GeneKind::Real if start < end => rng.gen_range(start..=end),
+            GeneKind::Real => start,
+            GeneKind::Integer => rng.gen_range(start.ceil() as i64..=end.floor() as i64) as f64,
+            GeneKind::Boolean => f64::from(u8::from(rng.gen::<bool>())),
+        }
+    }
+}
+
+/// Fields of a solution as a flat vector of genes, usually implemented with
+/// `#[derive(Encoding)]` and `#[gene(range = min..=max)]` on `f64`, integer and `bool` fields.
+pub trait Encoding: Sized {
+    fn genes() -> Vec<Gene>;
+    fn to_vec(&self) -> Vec<f64>;
+    /// Sets the encoded fields to `values` decoded with `Gene::decode`.
+    /// Panics when there are fewer values than genes.
+    fn set_from_slice(&mut self, values: &[f64]);
+    /// Encoded fields set from `values`, the other fields are `Default`.
+    fn from_slice(values: &[f64]) -> Self;
+
+    fn bounds() -> Vec<RangeInclusive<f64>> {
+        Self::genes().into_iter().map(|gene| gene.range).collect()
+    }
+
+    fn names() -> Vec<&'static str> {
+        Self::genes().into_iter().map(|gene| gene.name).collect()
+    }
+
+    fn random_init<R: Rng + ?Sized>(rng: &mut R) -> Self {
+        let values: Vec<f64> = Self::genes().iter().map(|gene| gene.sample(rng)).collect();
+        Self::from_slice(&values)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use super::Gene;
+    use crate::{
+        analysis::csv::{CsvRecord, Field},
+        base::{solution_attr, DerivedSolution, Encoding},
+    };
+
+    #[solution_attr]
+    #[derive(Clone, DerivedSolution, Encoding)]
+    struct Settings {
+        #[gene(range = -5.0..=5.0)]
+        rate: f64,
+        #[gene(range = 1..=10)]
+        workers: u32,
+        #[gene]
+        cache: bool,
+        label: String,
+    }
+
+    #[test]
+    fn derives_genes_and_flat_view() {
+        assert_eq!(
+            vec![
+                Gene::real("rate", -5.0..=5.0),
+                Gene::integer("workers", 1.0..=10.0),
+                Gene::boolean("cache"),
+            ],
+            Settings::genes()
+        );
+
+        let mut settings = Settings::from_slice(&[7.5, 3.6, 0.2]);
+        assert_eq!(
+            (5.0, 4, false),
+            (settings.rate, settings.workers, settings.cache)
+        );
+        assert_eq!("", settings.label);
+        assert!(settings.eval.value.is_nan());
+
+        settings.set_from_slice(&[-1.5, -3.0, 0.9]);
+        assert_eq!(vec![-1.5, 1.0, 1.0], settings.to_vec());
+    }
+
+    #[test]
+    fn derives_csv_record_of_genes() {
+        let settings = Settings::from_slice(&[0.5, 3.0, 1.0]);
+
+        assert_eq!(vec!["rate", "workers", "cache"], Settings::columns());
+        assert_eq!(
+            vec![Field::Float(0.5), Field::UInt(3), Field::Bool(true)],
+            settings.fields()
+        );
+    }
+
+    #[test]
+    fn random_init_stays_in_bounds() {
+        let mut rng = StdRng::seed_from_u64(22);
+        for _ in 0..100 {
+            let settings = Settings::random_init(&mut rng);
+            assert!((-5.0..=5.0).contains(&settings.rate));
+            assert!((1..=10).contains(&settings.workers));
+        }
+    }
+}
@@ -1,14 +1,39 @@
 use std::{fmt::Display, hash::Hash};
 
-pub use self::criterion::Criterion;
+use crate::Result;
+
+pub(crate) use self::observer::notify;
+pub use self::{
+    cache::EvaluationCache,
+    constraints::ConstraintHandling,
+    counter::EvaluationCounter,
+    criterion::{Criterion, CriterionState, EvaluationFn},
+    encoding::{Encoding, Gene, GeneKind},
+    observer::{diversity, Event, Iteration, Observer},
+    pareto::{crowding_distance, MultiCriterion},
+};
+mod cache;
+mod constraints;
+mod counter;
 mod criterion;
+mod encoding;
+mod observer;
+mod pareto;
 
-pub use optima_macros::{solution_attr, DerivedSolution};
+pub use optima_macros::{solution_attr, DerivedSolution, Encoding};
 
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Evaluation {
     pub value: f64,
     pub is_feasible: bool,
+    /// Absolute value of the penalty, 0 for feasible solutions.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub violation: f64,
+    /// Violation of every constraint registered on the criterion, in the order of registration.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub violations: Vec<f64>,
+    pub objectives: Vec<f64>,
 }
 
 impl Default for Evaluation {
@@ -16,6 +41,9 @@ impl Default for Evaluation {
         Self {
             value: f64::NAN,
             is_feasible: false,
+            violation: 0.0,
+            violations: vec![],
+            objectives: vec![],
         }
     }
 }
@@ -25,16 +53,57 @@ where
     S: Solution,
     P: Problem,
 {
-    fn solve(&mut self, problem: P, criterion: &mut Criterion<P, S>) -> Vec<S>;
+    fn solve(&mut self, problem: P, criterion: &mut Criterion<P, S>) -> Result<Vec<S>>;
     fn reset(&mut self);
 }
 
+/// Counterpart of `OptAlgorithm` for vector-valued objectives, returns the non-dominated set.
+pub trait MultiOptAlgorithm<'a, P, S>: Display
+where
+    S: Solution,
+    P: Problem,
+{
+    fn solve(&mut self, problem: P, criterion: &mut MultiCriterion<P, S>) -> Result<Vec<S>>;
+    fn reset(&mut self);
+}
+
+/// Receives a snapshot of the algorithm state, see `register_checkpoint` of every algorithm.
+pub type CheckpointFn<'a, T> = dyn FnMut(&T) + 'a;
+
 pub trait Solution: Clone {
     fn get_value(&self) -> f64;
     fn get_eval(&self) -> &Evaluation;
     fn get_eval_mut(&mut self) -> &mut Evaluation;
 }
 
+/// Change of the penalty and of the value caused by a move. Deltas are only applied to feasible
+/// solutions, whose penalty is 0, so the change of the penalty is also the penalty after the move.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Delta {
+    pub penalty: f64,
+    pub value: f64,
+}
+
+/// Move descriptor returned by a change function, lets `Criterion::evaluate_move` update
+/// the evaluation instead of recomputing it. `()` always falls back to a full evaluation.
+pub trait DeltaMove<P, S> {
+    /// Change caused by applying the move to `before`, `None` when it can't be computed cheaply.
+    fn delta(&self, problem: &P, before: &S) -> Option<Delta>;
+}
+
+impl<P, S> DeltaMove<P, S> for () {
+    fn delta(&self, _problem: &P, _before: &S) -> Option<Delta> {
+        None
+    }
+}
+
+/// Solution encoded as a fixed-length sequence of genes, lets `genetic::operators` work on it.
+pub trait Chromosome: Solution {
+    type Gene;
+    fn genes(&self) -> &[Self::Gene];
+    fn genes_mut(&mut self) -> &mut [Self::Gene];
+}
+
 #[derive(Debug, Eq, Hash, PartialEq)]
 pub enum State {
     Best,
@@ -44,7 +113,61 @@ pub enum State {
 
 pub trait Problem {}
 
-// pub type AnnealingInsightFn<S, P, C> = dyn FnMut(&C, u32, &P, &S, &S, bool);
-// pub type SwarmInsightFn              = dyn FnMut(&FnProblem<RangeInclusive<f64>>, &Vec<Particle>, usize) -> bool;
-// pub type GeneticInsightFn<S>         = dyn Fn(u32, &Vec<S>);
+#[cfg(test)]
+mod tests {
+    use std::fmt::Debug;
+
+    use super::{DerivedSolution, Evaluation, Solution};
+
+    #[derive(Clone, DerivedSolution)]
+    struct Generic<'a, T: Clone, const N: usize>
+    where
+        T: Debug,
+    {
+        genes: [T; N],
+        name: &'a str,
+        eval: Evaluation,
+    }
+
+    #[derive(Clone, DerivedSolution)]
+    #[solution(eval = "evaluation")]
+    struct Renamed {
+        evaluation: Evaluation,
+    }
+
+    #[derive(Clone, DerivedSolution)]
+    #[solution(eval = "1")]
+    struct Tuple(Vec<bool>, Evaluation);
 
+    fn set_value<S: Solution>(solution: &mut S, value: f64) {
+        solution.get_eval_mut().value = value;
+    }
+
+    #[test]
+    fn derives_generic_solution() {
+        let mut solution = Generic {
+            genes: [1u8, 2, 3],
+            name: "generic",
+            eval: Evaluation::default(),
+        };
+        set_value(&mut solution, 4.0);
+
+        assert_eq!(4.0, solution.get_value());
+        assert_eq!([1, 2, 3], solution.genes);
+        assert_eq!("generic", solution.name);
+    }
+
+    #[test]
+    fn derives_renamed_eval_field() {
+        let mut renamed = Renamed {
+            evaluation: Evaluation::default(),
+        };
+        set_value(&mut renamed, 2.0);
+        assert_eq!(2.0, renamed.evaluation.value);
+
+        let mut tuple = Tuple(vec![true], Evaluation::default());
+        set_value(&mut tuple, -1.0);
+        assert_eq!(-1.0, tuple.get_eval().value);
+        assert_eq!(vec![true], tuple.0);
+    }
+}
@@ -0,0 +1,65 @@
+use crate::{analysis::stats, base::Solution};
+
+/// Something that happened during `solve`, passed to the registered `Observer`.
+pub enum Event<'e, S> {
+    RunStarted,
+    IterationFinished(Iteration<'e, S>),
+    NewBest {
+        iteration: u32,
+        best: &'e S,
+    },
+    /// Candidate the algorithm moved to.
+    Accepted {
+        iteration: u32,
+        candidate: &'e S,
+    },
+    /// Candidate the algorithm evaluated and threw away.
+    Rejected {
+        iteration: u32,
+        candidate: &'e S,
+    },
+    RunFinished {
+        iterations: u32,
+        best: &'e S,
+    },
+}
+
+/// State of the algorithm at the end of an iteration (a generation for population based ones).
+pub struct Iteration<'e, S> {
+    pub iteration: u32,
+    /// Best solution found so far.
+    pub best: &'e S,
+    /// Solution the search continues from, the best of the current population for population
+    /// based algorithms.
+    pub current: &'e S,
+    pub temperature: Option<f64>,
+    /// See `diversity`, only for population based algorithms.
+    pub diversity: Option<f64>,
+}
+
+/// Receives the events of a run, see `register_observer` of every algorithm.
+/// Implemented for closures taking `&Event<S>`.
+pub trait Observer<S> {
+    fn notify(&mut self, event: &Event<S>);
+}
+
+impl<S, F> Observer<S> for F
+where
+    F: FnMut(&Event<S>),
+{
+    fn notify(&mut self, event: &Event<S>) {
+        self(event)
+    }
+}
+
+/// Standard deviation of the values of the population.
+pub fn diversity<S: Solution>(population: &[S]) -> f64 {
+    let values: Vec<f64> = population.iter().map(|s| s.get_value()).collect();
+    stats::std_dev(&values)
+}
+
+pub(crate) fn notify<S>(observer: &mut Option<&mut dyn Observer<S>>, event: &Event<S>) {
+    if let Some(observer) = observer {
+        observer.notify(event);
+    }
+}
@@ -0,0 +1,281 @@
+use std::cmp::Ordering;
+
+use crate::base::{criterion::EvaluationFn, Evaluation, Problem, Solution};
+
+pub struct MultiCriterion<'a, P, S>
+where
+    S: Solution,
+{
+    penalty: &'a EvaluationFn<S, P>,
+    objectives: Vec<&'a EvaluationFn<S, P>>,
+    pub is_minimization: Vec<bool>,
+}
+
+impl<'a, P, S> MultiCriterion<'a, P, S>
+where
+    S: Solution,
+    P: Problem,
+{
+    /// Every objective is paired with its own `is_minimization` flag.
+    pub fn new(
+        penalty: &'a EvaluationFn<S, P>,
+        objectives: Vec<(&'a EvaluationFn<S, P>, bool)>,
+    ) -> Self {
+        let (objectives, is_minimization) = objectives.into_iter().unzip();
+        Self {
+            penalty,
+            objectives,
+            is_minimization,
+        }
+    }
+
+    pub fn evaluate(&self, problem: &P, solution: &mut S) {
+        let violation = (self.penalty)(problem, solution).abs();
+        let is_feasible = violation == 0.0;
+
+        let mut objectives = Vec::with_capacity(self.objectives.len());
+        if is_feasible {
+            for objective in &self.objectives {
+                objectives.push(objective(problem, solution));
+            }
+        }
+
+        let eval = solution.get_eval_mut();
+        eval.value = match is_feasible {
+            true => objectives.first().copied().unwrap_or(0.0),
+            false => violation,
+        };
+        eval.is_feasible = is_feasible;
+        eval.violation = violation;
+        eval.objectives = objectives;
+    }
+
+    /// Constrained Pareto dominance: feasible beats infeasible, two infeasible evaluations
+    /// are compared by violation and two feasible ones by their objectives.
+    pub fn dominates(&self, first: &Evaluation, second: &Evaluation) -> bool {
+        if first.is_feasible && !second.is_feasible {
+            return true;
+        };
+
+        if !first.is_feasible && second.is_feasible {
+            return false;
+        }
+
+        // Lower violation
+        if !second.is_feasible {
+            return first.value < second.value;
+        }
+
+        let mut better_in_any = false;
+        for (i, is_minimization) in self.is_minimization.iter().enumerate() {
+            let (a, b) = (first.objectives[i], second.objectives[i]);
+            let (better, worse) = match is_minimization {
+                true => (a < b, a > b),
+                false => (a > b, a < b),
+            };
+            if worse {
+                return false;
+            }
+            better_in_any |= better;
+        }
+
+        better_in_any
+    }
+
+    /// Fast non-dominated sort. Returns indexes into `population` grouped by front,
+    /// the first front being the non-dominated set.
+    pub fn fronts(&self, population: &[S]) -> Vec<Vec<usize>> {
+        let n = population.len();
+        let mut dominated_by = vec![0; n];
+        let mut dominates = vec![vec![]; n];
+        let mut fronts = vec![vec![]];
+
+        for i in 0..n {
+            for j in i + 1..n {
+                let (a, b) = (population[i].get_eval(), population[j].get_eval());
+                if self.dominates(a, b) {
+                    dominates[i].push(j);
+                    dominated_by[j] += 1;
+                } else if self.dominates(b, a) {
+                    dominates[j].push(i);
+                    dominated_by[i] += 1;
+                }
+            }
+            if dominated_by[i] == 0 {
+                fronts[0].push(i);
+            }
+        }
+
+        let mut current = 0;
+        while !fronts[current].is_empty() {
+            let mut next = vec![];
+            for &i in &fronts[current] {
+                for &j in &dominates[i] {
+                    dominated_by[j] -= 1;
+                    if dominated_by[j] == 0 {
+                        next.push(j);
+                    }
+                }
+            }
+            fronts.push(next);
+            current += 1;
+        }
+        fronts.pop();
+
+        fronts
+    }
+
+    pub fn non_dominated(&self, population: &[S]) -> Vec<S> {
+        match self.fronts(population).first() {
+            Some(front) => front.iter().map(|&i| population[i].clone()).collect(),
+            None => vec![],
+        }
+    }
+}
+
+/// Crowding distance of every member of `front` (indexes into `population`), in the same order.
+/// Boundary solutions get `f64::INFINITY`.
+pub fn crowding_distance<S: Solution>(population: &[S], front: &[usize]) -> Vec<f64> {
+    let mut distances = vec![0.0; front.len()];
+    let objectives_len = match front.first() {
+        Some(&i) => population[i].get_eval().objectives.len(),
+        None => return distances,
+    };
+
+    let mut order: Vec<usize> = (0..front.len()).collect();
+    for m in 0..objectives_len {
+        let objective = |k: usize| population[front[k]].get_eval().objectives[m];
+        order.sort_by(|&a, &b| {
+            objective(a)
+                .partial_cmp(&objective(b))
+                .unwrap_or(Ordering::Equal)
+        });
+
+        let first = order[0];
+        let last = order[order.len() - 1];
+        distances[first] = f64::INFINITY;
+        distances[last] = f64::INFINITY;
+
+        let range = objective(last) - objective(first);
+        if range == 0.0 {
+            continue;
+        }
+        for k in 1..order.len().saturating_sub(1) {
+            distances[order[k]] += (objective(order[k + 1]) - objective(order[k - 1])) / range;
+        }
+    }
+
+    distances
+}
+
+#[cfg(test)]
+mod tests {
+    use optima_macros::{solution_attr, DerivedSolution};
+
+    use super::{crowding_distance, MultiCriterion};
+    use crate::base::{Evaluation, Problem, Solution};
+
+    #[solution_attr]
+    #[derive(Clone, DerivedSolution)]
+    struct TestSolution {
+        x: f64,
+    }
+
+    #[derive(Clone, Copy)]
+    struct TestProblem;
+    impl Problem for TestProblem {}
+
+    fn penalty(_: &TestProblem, _: &TestSolution) -> f64 {
+        0.0
+    }
+
+    fn f1(_: &TestProblem, solution: &TestSolution) -> f64 {
+        solution.x.powi(2)
+    }
+
+    fn f2(_: &TestProblem, solution: &TestSolution) -> f64 {
+        (solution.x - 2.0).powi(2)
+    }
+
+    fn evaluated(
+        criterion: &MultiCriterion<TestProblem, TestSolution>,
+        xs: &[f64],
+    ) -> Vec<TestSolution> {
+        xs.iter()
+            .map(|&x| {
+                let mut solution = TestSolution {
+                    x,
+                    eval: Evaluation::default(),
+                };
+                criterion.evaluate(&TestProblem, &mut solution);
+                solution
+            })
+            .collect()
+    }
+
+    #[test]
+    fn dominates_requires_better_in_one_and_not_worse_in_any() {
+        let criterion = MultiCriterion::new(&penalty, vec![(&f1, true), (&f2, true)]);
+        let population = evaluated(&criterion, &[1.0, 3.0, 0.5]);
+
+        // x = 1.0 -> (1, 1), x = 3.0 -> (9, 1), x = 0.5 -> (0.25, 2.25)
+        let a = population[0].get_eval();
+        let b = population[1].get_eval();
+        let c = population[2].get_eval();
+        assert!(criterion.dominates(a, b));
+        assert!(!criterion.dominates(b, a));
+        assert!(!criterion.dominates(a, c));
+        assert!(!criterion.dominates(c, a));
+        assert!(!criterion.dominates(a, a));
+    }
+
+    #[test]
+    fn dominates_prefers_feasible() {
+        let criterion =
+            MultiCriterion::<TestProblem, TestSolution>::new(&penalty, vec![(&f1, true)]);
+        let feasible = Evaluation {
+            value: 100.0,
+            is_feasible: true,
+            violation: 0.0,
+            violations: vec![],
+            objectives: vec![100.0],
+        };
+        let infeasible = Evaluation {
+            value: 1.0,
+            is_feasible: false,
+            violation: 1.0,
+            violations: vec![],
+            objectives: vec![],
+        };
+
+        assert!(criterion.dominates(&feasible, &infeasible));
+        assert!(!criterion.dominates(&infeasible, &feasible));
+    }
+
+    #[test]
+    fn fronts_groups_by_rank() {
+        let criterion = MultiCriterion::new(&penalty, vec![(&f1, true), (&f2, true)]);
+        let population = evaluated(&criterion, &[3.0, 1.0, 0.5, 4.0, 1.5]);
+
+        let fronts = criterion.fronts(&population);
+
+        let mut first = fronts[0].clone();
+        first.sort();
+        assert_eq!(vec![1, 2, 4], first);
+        assert_eq!(vec![0], fronts[1]);
+        assert_eq!(vec![3], fronts[2]);
+        assert_eq!(3, criterion.non_dominated(&population).len());
+    }
+
+    #[test]
+    fn crowding_distance_boundaries_are_infinite() {
+        let criterion = MultiCriterion::new(&penalty, vec![(&f1, true), (&f2, true)]);
+        let population = evaluated(&criterion, &[0.0, 1.0, 2.0, 0.5]);
+
+        let distances = crowding_distance(&population, &[0, 1, 2, 3]);
+
+        assert_eq!(f64::INFINITY, distances[0]);
+        assert_eq!(f64::INFINITY, distances[2]);
+        assert!(distances[1].is_finite() && distances[1] > distances[3]);
+    }
+}
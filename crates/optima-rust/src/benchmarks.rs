@@ -0,0 +1,319 @@
+//! Continuous test functions with known global minima.
+//! <https://en.wikipedia.org/wiki/Test_functions_for_optimization>
+
+use std::f64::consts::{E, PI};
+use std::ops::RangeInclusive;
+
+use crate::{swarm::FnProblem, Result};
+
+pub type BenchmarkFn = fn(&[f64]) -> f64;
+
+/// Function to minimize together with its search space and known global minimum.
+#[derive(Clone, Debug)]
+pub struct Benchmark {
+    pub name: String,
+    pub func: BenchmarkFn,
+    pub bounds: Vec<RangeInclusive<f64>>,
+    /// One of the points where `minimum` is reached.
+    pub minimum_at: Vec<f64>,
+    pub minimum: f64,
+}
+
+impl Benchmark {
+    pub fn new(
+        name: &str,
+        func: BenchmarkFn,
+        bounds: Vec<RangeInclusive<f64>>,
+        minimum_at: Vec<f64>,
+        minimum: f64,
+    ) -> Self {
+        Self {
+            name: name.to_string(),
+            func,
+            bounds,
+            minimum_at,
+            minimum,
+        }
+    }
+
+    /// Same range for each of the `dimensions` coordinates, minimum reached at `[at; dimensions]`.
+    fn scalable(
+        name: &str,
+        func: BenchmarkFn,
+        dimensions: usize,
+        range: RangeInclusive<f64>,
+        at: f64,
+        minimum: f64,
+    ) -> Self {
+        Self::new(
+            name,
+            func,
+            vec![range; dimensions],
+            vec![at; dimensions],
+            minimum,
+        )
+    }
+
+    pub fn dimensions(&self) -> usize {
+        self.bounds.len()
+    }
+
+    pub fn evaluate(&self, x: &[f64]) -> f64 {
+        (self.func)(x)
+    }
+
+    /// How far `value` is from the known minimum.
+    pub fn error(&self, value: f64) -> f64 {
+        value - self.minimum
+    }
+
+    /// Search space of the benchmark for `ParticleSwarm`.
+    pub fn problem(&self, id: u32) -> Result<FnProblem<RangeInclusive<f64>>> {
+        FnProblem::new(id, self.bounds.clone())
+    }
+
+    /// Scalable functions in `dimensions` dimensions, plus the fixed 2D ones when `dimensions` is 2.
+    pub fn suite(dimensions: usize) -> Vec<Self> {
+        let mut suite = vec![
+            Self::rastrigin(dimensions),
+            Self::ackley(dimensions),
+            Self::rosenbrock(dimensions),
+            Self::griewank(dimensions),
+            Self::schwefel(dimensions),
+            Self::levy(dimensions),
+        ];
+
+        if dimensions == 2 {
+            suite.extend([
+                Self::booth(),
+                Self::simple(),
+                Self::cormick(),
+                Self::bukin(),
+                Self::himmelblau(),
+                Self::eggholder(),
+            ]);
+        }
+
+        suite
+    }
+
+    pub fn booth() -> Self {
+        Self::new(
+            "booth",
+            booth,
+            vec![-10.0..=10.0, -10.0..=10.0],
+            vec![1.0, 3.0],
+            0.0,
+        )
+    }
+
+    pub fn simple() -> Self {
+        Self::new(
+            "simple",
+            simple,
+            vec![-10.0..=10.0, -10.0..=10.0],
+            vec![-10.0, 10.0],
+            -100.0,
+        )
+    }
+
+    pub fn cormick() -> Self {
+        Self::new(
+            "cormick",
+            cormick,
+            vec![-1.5..=4.0, -3.0..=4.0],
+            vec![-0.54719755, -1.54719755],
+            -1.91322295,
+        )
+    }
+
+    pub fn bukin() -> Self {
+        Self::new(
+            "bukin",
+            bukin,
+            vec![-15.0..=-5.0, -3.0..=3.0],
+            vec![-10.0, 1.0],
+            0.0,
+        )
+    }
+
+    /// Has four global minima, `minimum_at` is the one at `(3, 2)`.
+    pub fn himmelblau() -> Self {
+        Self::new(
+            "himmelblau",
+            himmelblau,
+            vec![-5.0..=5.0, -5.0..=5.0],
+            vec![3.0, 2.0],
+            0.0,
+        )
+    }
+
+    pub fn eggholder() -> Self {
+        Self::new(
+            "eggholder",
+            eggholder,
+            vec![-512.0..=512.0, -512.0..=512.0],
+            vec![512.0, 404.2319],
+            -959.6407,
+        )
+    }
+
+    pub fn rastrigin(dimensions: usize) -> Self {
+        Self::scalable("rastrigin", rastrigin, dimensions, -5.12..=5.12, 0.0, 0.0)
+    }
+
+    pub fn ackley(dimensions: usize) -> Self {
+        Self::scalable("ackley", ackley, dimensions, -32.768..=32.768, 0.0, 0.0)
+    }
+
+    pub fn rosenbrock(dimensions: usize) -> Self {
+        Self::scalable("rosenbrock", rosenbrock, dimensions, -5.0..=10.0, 1.0, 0.0)
+    }
+
+    pub fn griewank(dimensions: usize) -> Self {
+        Self::scalable("griewank", griewank, dimensions, -600.0..=600.0, 0.0, 0.0)
+    }
+
+    pub fn schwefel(dimensions: usize) -> Self {
+        Self::scalable(
+            "schwefel",
+            schwefel,
+            dimensions,
+            -500.0..=500.0,
+            420.9687,
+            0.0,
+        )
+    }
+
+    pub fn levy(dimensions: usize) -> Self {
+        Self::scalable("levy", levy, dimensions, -10.0..=10.0, 1.0, 0.0)
+    }
+}
+
+pub fn booth(x: &[f64]) -> f64 {
+    (x[0] + 2.0 * x[1] - 7.0).powi(2) + (2.0 * x[0] + x[1] - 5.0).powi(2)
+}
+
+pub fn simple(x: &[f64]) -> f64 {
+    x[0] * x[1]
+}
+
+/// McCormick function.
+pub fn cormick(x: &[f64]) -> f64 {
+    (x[0] + x[1]).sin() + (x[0] - x[1]).powi(2) - 1.5 * x[0] + 2.5 * x[1] + 1.0
+}
+
+/// Bukin function N.6.
+pub fn bukin(x: &[f64]) -> f64 {
+    100.0 * (x[1] - 0.01 * x[0].powi(2)).abs().sqrt() + 0.01 * (x[0] + 10.0).abs()
+}
+
+pub fn himmelblau(x: &[f64]) -> f64 {
+    (x[0].powi(2) + x[1] - 11.0).powi(2) + (x[0] + x[1].powi(2) - 7.0).powi(2)
+}
+
+pub fn eggholder(x: &[f64]) -> f64 {
+    let y = x[1] + 47.0;
+    -y * (x[0] / 2.0 + y).abs().sqrt().sin() - x[0] * (x[0] - y).abs().sqrt().sin()
+}
+
+pub fn rastrigin(x: &[f64]) -> f64 {
+    10.0 * x.len() as f64
+        + x.iter()
+            .map(|xi| xi.powi(2) - 10.0 * (2.0 * PI * xi).cos())
+            .sum::<f64>()
+}
+
+pub fn ackley(x: &[f64]) -> f64 {
+    let n = x.len() as f64;
+    let squares = x.iter().map(|xi| xi.powi(2)).sum::<f64>() / n;
+    let cosines = x.iter().map(|xi| (2.0 * PI * xi).cos()).sum::<f64>() / n;
+
+    -20.0 * (-0.2 * squares.sqrt()).exp() - cosines.exp() + 20.0 + E
+}
+
+pub fn rosenbrock(x: &[f64]) -> f64 {
+    x.windows(2)
+        .map(|w| 100.0 * (w[1] - w[0].powi(2)).powi(2) + (1.0 - w[0]).powi(2))
+        .sum()
+}
+
+pub fn griewank(x: &[f64]) -> f64 {
+    let sum = x.iter().map(|xi| xi.powi(2)).sum::<f64>() / 4000.0;
+    let product = x
+        .iter()
+        .enumerate()
+        .map(|(i, xi)| (xi / ((i + 1) as f64).sqrt()).cos())
+        .product::<f64>();
+
+    1.0 + sum - product
+}
+
+pub fn schwefel(x: &[f64]) -> f64 {
+    418.9829 * x.len() as f64 - x.iter().map(|xi| xi * xi.abs().sqrt().sin()).sum::<f64>()
+}
+
+/// NaN without dimensions, the function needs a first and a last coordinate.
+pub fn levy(x: &[f64]) -> f64 {
+    let w: Vec<f64> = x.iter().map(|xi| 1.0 + (xi - 1.0) / 4.0).collect();
+    let (first, last) = match (w.first(), w.last()) {
+        (Some(first), Some(last)) => (*first, *last),
+        _ => return f64::NAN,
+    };
+
+    (PI * first).sin().powi(2)
+        + w[..w.len() - 1]
+            .iter()
+            .map(|wi| (wi - 1.0).powi(2) * (1.0 + 10.0 * (PI * wi + 1.0).sin().powi(2)))
+            .sum::<f64>()
+        + (last - 1.0).powi(2) * (1.0 + (2.0 * PI * last).sin().powi(2))
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    use super::{levy, Benchmark};
+
+    #[test]
+    fn known_minima_are_reached() {
+        for dimensions in [2, 5] {
+            for benchmark in Benchmark::suite(dimensions) {
+                let value = benchmark.evaluate(&benchmark.minimum_at);
+                assert!(
+                    benchmark.error(value).abs() < 1e-3,
+                    "{} in {} dimensions gave {}",
+                    benchmark.name,
+                    dimensions,
+                    value
+                );
+                assert!(benchmark
+                    .bounds
+                    .iter()
+                    .zip(&benchmark.minimum_at)
+                    .all(|(range, x)| range.contains(x)));
+            }
+        }
+    }
+
+    #[test]
+    fn levy_without_dimensions_is_nan() {
+        assert!(levy(&[]).is_nan());
+        assert!(levy(&[1.0]).abs() < 1e-12);
+    }
+
+    #[test]
+    fn random_points_are_not_below_minimum() {
+        let mut rng = StdRng::seed_from_u64(17);
+        for benchmark in Benchmark::suite(2) {
+            for _ in 0..1000 {
+                let x: Vec<f64> = benchmark
+                    .bounds
+                    .iter()
+                    .map(|range| rng.gen_range(range.clone()))
+                    .collect();
+                assert!(benchmark.error(benchmark.evaluate(&x)) > -1e-3);
+            }
+        }
+    }
+}
@@ -0,0 +1,77 @@
+use std::{fmt::Display, io};
+
+use rand::distributions::WeightedError;
+
+#[derive(Debug)]
+pub enum Error {
+    Io(io::Error),
+    Weights(WeightedError),
+    EmptyPopulation,
+    /// Range is unbounded or its start is greater than its end
+    InvalidRange,
+    /// Argument outside of its domain, the message says which and why
+    InvalidArgument(String),
+    /// Criterion produced a value that cannot be compared
+    NotANumber,
+    WorkerPanicked,
+    /// CSV record has a different number of fields than columns
+    FieldCount {
+        columns: usize,
+        fields: usize,
+    },
+    /// Paired test got samples of different lengths
+    UnpairedSamples {
+        first: usize,
+        second: usize,
+    },
+    /// Malformed input file, `line` is 1-based
+    Parse {
+        line: usize,
+        message: String,
+    },
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+impl Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::Io(e) => write!(f, "IO error: {e}"),
+            Error::Weights(e) => write!(f, "Invalid selection weights: {e}"),
+            Error::EmptyPopulation => write!(f, "Population is empty"),
+            Error::InvalidRange => write!(f, "Range is unbounded or empty"),
+            Error::InvalidArgument(message) => write!(f, "Invalid argument: {message}"),
+            Error::NotANumber => write!(f, "Evaluation produced NaN"),
+            Error::WorkerPanicked => write!(f, "Worker thread panicked"),
+            Error::FieldCount { columns, fields } => {
+                write!(f, "Record has {fields} fields for {columns} columns")
+            }
+            Error::UnpairedSamples { first, second } => {
+                write!(f, "Paired samples have {first} and {second} values")
+            }
+            Error::Parse { line, message } => write!(f, "Parse error at line {line}: {message}"),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io(e) => Some(e),
+            Error::Weights(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(e: io::Error) -> Self {
+        Error::Io(e)
+    }
+}
+
+impl From<WeightedError> for Error {
+    fn from(e: WeightedError) -> Self {
+        Error::Weights(e)
+    }
+}
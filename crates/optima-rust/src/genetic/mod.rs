@@ -1,100 +1,511 @@
 use std::fmt::Display;
 
-use rand::{prelude::ThreadRng, thread_rng};
+use rand::{prelude::ThreadRng, thread_rng, Rng};
 
+pub mod nsga2;
+pub mod operators;
 pub mod selection;
 
-use crate::base::{OptAlgorithm, Problem, Solution};
+use crate::{
+    annealing::stop::StopCriteria,
+    base::{
+        diversity, notify, CheckpointFn, Criterion, CriterionState, Event, Iteration, Observer,
+        OptAlgorithm, Problem, Solution,
+    },
+    Result,
+};
 
-pub type SelectionFn<S> = dyn Fn(usize, &Vec<S>, &mut ThreadRng) -> Vec<S>;
-pub type ChangePopFn<S> = dyn Fn(&mut Vec<S>, &mut ThreadRng);
-pub type GeneticInsightFn<S> = dyn Fn(u32, &Vec<S>);
+pub type SelectionFn<S, R = ThreadRng> = dyn Fn(usize, &Vec<S>, &mut R) -> Result<Vec<S>>;
+pub type ChangePopFn<S, R = ThreadRng> = dyn Fn(&mut Vec<S>, &mut R);
 
-pub struct GeneticAlgorithm<'a, S>
+/// How offspring replace the previous population, specimens are ranked by `Criterion::is_first_better`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Replacement {
+    /// Offspring replace the whole population except for the `elite` best specimens,
+    /// which survive unchanged in place of the worst offspring.
+    Generational { elite: usize },
+    /// Only the `replaced` worst specimens are replaced, by the best offspring.
+    SteadyState { replaced: usize },
+}
+
+impl Default for Replacement {
+    fn default() -> Self {
+        Replacement::Generational { elite: 0 }
+    }
+}
+
+/// Population and generator after `generation` generations, see `GeneticAlgorithm::resume`.
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GeneticCheckpoint<S, SC, R> {
+    pub population: Vec<S>,
+    pub stop_criteria: SC,
+    pub rng: R,
+    pub generation: u32,
+    pub criterion: CriterionState,
+}
+
+/// Runs until `stop_criteria` stop, which are updated with the value of the best specimen
+/// after every generation.
+pub struct GeneticAlgorithm<'a, S, SC, R = ThreadRng>
 where
     S: Solution,
+    SC: StopCriteria,
+    R: Rng,
 {
     pub population: Vec<S>,
-    pub select: &'a SelectionFn<S>,
-    pub change: &'a ChangePopFn<S>,
-    pub generations: u32,
+    pub select: &'a SelectionFn<S, R>,
+    pub change: &'a ChangePopFn<S, R>,
+    stop_criteria: SC,
     initial_population: Vec<S>,
     population_cap: usize,
-    insight: Option<&'a mut GeneticInsightFn<S>>,
+    replacement: Replacement,
+    observer: Option<&'a mut dyn Observer<S>>,
+    checkpoint: Option<&'a mut CheckpointFn<'a, GeneticCheckpoint<S, SC, R>>>,
+    checkpoint_every: u32,
+    rng: R,
+    #[cfg(feature = "parallel")]
+    parallel: bool,
 }
 
-impl<'a, S> GeneticAlgorithm<'a, S>
+impl<'a, S, SC> GeneticAlgorithm<'a, S, SC, ThreadRng>
 where
     S: Solution,
+    SC: StopCriteria,
 {
     pub fn new(
         population_cap: usize,
         population: Vec<S>,
         change: &'a ChangePopFn<S>,
         select: &'a SelectionFn<S>,
-        generations: u32,
-        insight: Option<&'a mut GeneticInsightFn<S>>,
+        stop_criteria: SC,
+        observer: Option<&'a mut dyn Observer<S>>,
+    ) -> Self {
+        Self::with_rng(
+            population_cap,
+            population,
+            change,
+            select,
+            stop_criteria,
+            observer,
+            thread_rng(),
+        )
+    }
+}
+
+impl<'a, S, SC, R> GeneticAlgorithm<'a, S, SC, R>
+where
+    S: Solution,
+    SC: StopCriteria,
+    R: Rng + Clone,
+{
+    /// Pass a seeded generator (e.g. `StdRng::seed_from_u64`) to make a run reproducible.
+    pub fn with_rng(
+        population_cap: usize,
+        population: Vec<S>,
+        change: &'a ChangePopFn<S, R>,
+        select: &'a SelectionFn<S, R>,
+        stop_criteria: SC,
+        observer: Option<&'a mut dyn Observer<S>>,
+        rng: R,
     ) -> Self {
         Self {
-            generations,
+            stop_criteria,
             initial_population: population.clone(),
             population,
             select,
             change,
             population_cap,
-            insight,
+            replacement: Replacement::default(),
+            observer,
+            checkpoint: None,
+            checkpoint_every: 0,
+            rng,
+            #[cfg(feature = "parallel")]
+            parallel: false,
         }
     }
 
-    pub fn register_insight(&mut self, insight: &'a mut GeneticInsightFn<S>) {
-        self.insight = Some(insight);
+    /// Evaluate every generation on the rayon thread pool instead of one specimen at a time.
+    #[cfg(feature = "parallel")]
+    pub fn evaluate_in_parallel(&mut self, parallel: bool) {
+        self.parallel = parallel;
     }
-}
 
-impl<S, P> OptAlgorithm<'_, P, S> for GeneticAlgorithm<'_, S>
-where
-    S: Solution,
-    P: Problem,
-{
-    fn solve(&mut self, problem: P, criterion: &mut crate::base::Criterion<P, S>) -> Vec<S> {
-        let mut rng = thread_rng();
+    pub fn set_replacement(&mut self, replacement: Replacement) {
+        self.replacement = replacement;
+    }
+
+    fn evaluate<P: Problem + Sync>(
+        &self,
+        problem: &P,
+        criterion: &Criterion<P, S>,
+        population: &mut [S],
+    ) where
+        S: Send,
+    {
+        #[cfg(feature = "parallel")]
+        if self.parallel {
+            criterion.par_evaluate_all(problem, population);
+            return;
+        }
+
+        criterion.evaluate_all(problem, population);
+    }
+
+    /// Builds the next population from the current (evaluated) one and the evaluated offspring.
+    fn replace<P: Problem>(
+        &mut self,
+        mut offspring: Vec<S>,
+        criterion: &Criterion<P, S>,
+    ) -> Result<()> {
+        match self.replacement {
+            Replacement::Generational { elite: 0 } => {
+                self.population = offspring;
+            }
+            Replacement::Generational { elite } => {
+                criterion.sort(&mut self.population)?;
+                criterion.sort(&mut offspring)?;
+
+                let elite = elite.min(self.population.len());
+                self.population.truncate(elite);
+                offspring.truncate(offspring.len().saturating_sub(elite));
+                self.population.append(&mut offspring);
+            }
+            Replacement::SteadyState { replaced } => {
+                criterion.sort(&mut self.population)?;
+                criterion.sort(&mut offspring)?;
+
+                let replaced = replaced.min(self.population.len()).min(offspring.len());
+                self.population.truncate(self.population.len() - replaced);
+                offspring.truncate(replaced);
+                self.population.append(&mut offspring);
+            }
+        }
+        Ok(())
+    }
+
+    pub fn register_observer(&mut self, observer: &'a mut dyn Observer<S>) {
+        self.observer = Some(observer);
+    }
+
+    /// Calls `checkpoint` every `every` generations with a snapshot the run can be resumed from.
+    pub fn register_checkpoint(
+        &mut self,
+        every: u32,
+        checkpoint: &'a mut CheckpointFn<'a, GeneticCheckpoint<S, SC, R>>,
+    ) {
+        self.checkpoint = Some(checkpoint);
+        self.checkpoint_every = every.max(1);
+    }
+
+    /// Continues a run from `checkpoint`, producing the same result as the run it was taken from.
+    /// The time limit and the cancel flag of the current stop criteria are kept.
+    pub fn resume<P: Problem + Sync>(
+        &mut self,
+        checkpoint: GeneticCheckpoint<S, SC, R>,
+        problem: P,
+        criterion: &mut Criterion<P, S>,
+    ) -> Result<Vec<S>>
+    where
+        S: Send,
+    {
+        self.population = checkpoint.population;
+        self.stop_criteria.resume_from(&checkpoint.stop_criteria);
+        self.rng = checkpoint.rng;
+        criterion.restore(checkpoint.criterion);
+
+        self.run(checkpoint.generation, problem, criterion)
+    }
+
+    fn run<P: Problem + Sync>(
+        &mut self,
+        first_generation: u32,
+        problem: P,
+        criterion: &mut Criterion<P, S>,
+    ) -> Result<Vec<S>>
+    where
+        S: Send,
+    {
+        let mut best = best_index(&self.population, criterion).map(|i| self.population[i].clone());
+        notify(&mut self.observer, &Event::RunStarted);
+
+        let mut generation = first_generation;
+        while !self.stop_criteria.should_stop() {
+            //Select parents form the previous population and breed offspring from them
+            let mut offspring =
+                (self.select)(self.population_cap, &self.population, &mut self.rng)?;
 
-        for generation in 0..self.generations {
-            //Select new population form the previous one
-            self.population = (self.select)(self.population_cap, &self.population, &mut rng);
+            (self.change)(&mut offspring, &mut self.rng);
 
-            (self.change)(&mut self.population, &mut rng);
+            self.evaluate(&problem, criterion, &mut offspring);
+            self.stop_criteria.evaluated(offspring.len());
+            self.replace(offspring, criterion)?;
 
-            for specimen in self.population.iter_mut() {
-                criterion.evaluate(&problem, specimen);
+            if let Some(i) = best_index(&self.population, criterion) {
+                let current = &self.population[i];
+                let improved = match &best {
+                    Some(best) => criterion.is_first_better(current.get_eval(), best.get_eval()),
+                    None => true,
+                };
+                if improved {
+                    best = Some(current.clone());
+                    notify(
+                        &mut self.observer,
+                        &Event::NewBest {
+                            iteration: generation,
+                            best: current,
+                        },
+                    );
+                }
+                if let Some(best) = &best {
+                    notify(
+                        &mut self.observer,
+                        &Event::IterationFinished(Iteration {
+                            iteration: generation,
+                            best,
+                            current,
+                            temperature: None,
+                            diversity: Some(diversity(&self.population)),
+                        }),
+                    );
+                }
+                criterion.next_iteration(current.get_eval());
+                self.stop_criteria.update(current.get_eval());
             }
+            generation += 1;
 
-            match &mut self.insight {
-                Some(f) => f(generation, &self.population),
-                _ => {}
+            if let Some(f) = &mut self.checkpoint {
+                if generation % self.checkpoint_every == 0 {
+                    f(&GeneticCheckpoint {
+                        population: self.population.clone(),
+                        stop_criteria: self.stop_criteria.clone(),
+                        rng: self.rng.clone(),
+                        generation,
+                        criterion: criterion.state(),
+                    });
+                }
             }
         }
 
-        for specimen in self.population.iter_mut() {
-            criterion.evaluate(&problem, specimen);
+        criterion.sort(&mut self.population)?;
+        if let Some(best) = &best {
+            notify(
+                &mut self.observer,
+                &Event::RunFinished {
+                    iterations: generation,
+                    best,
+                },
+            );
         }
-        self.population
-            .sort_by(|a, b| b.get_value().partial_cmp(&a.get_value()).unwrap());
 
-        self.population.clone()
+        Ok(self.population.clone())
+    }
+}
+
+/// Index of the best specimen according to `criterion`.
+fn best_index<P: Problem, S: Solution>(
+    population: &[S],
+    criterion: &Criterion<P, S>,
+) -> Option<usize> {
+    (0..population.len()).reduce(|best, i| {
+        match criterion.is_first_better(population[i].get_eval(), population[best].get_eval()) {
+            true => i,
+            false => best,
+        }
+    })
+}
+
+impl<S, SC, P, R> OptAlgorithm<'_, P, S> for GeneticAlgorithm<'_, S, SC, R>
+where
+    S: Solution + Send,
+    SC: StopCriteria,
+    P: Problem + Sync,
+    R: Rng + Clone,
+{
+    fn solve(&mut self, problem: P, criterion: &mut Criterion<P, S>) -> Result<Vec<S>> {
+        self.stop_criteria.reset();
+
+        let mut population = std::mem::take(&mut self.population);
+        self.evaluate(&problem, criterion, &mut population);
+        self.stop_criteria.evaluated(population.len());
+        self.population = population;
+
+        self.run(0, problem, criterion)
     }
 
     fn reset(&mut self) {
         self.population = self.initial_population.clone();
+        self.stop_criteria.reset();
     }
 }
 
-impl<'a, S: Solution> Display for GeneticAlgorithm<'a, S> {
+impl<'a, S: Solution, SC: StopCriteria, R: Rng> Display for GeneticAlgorithm<'a, S, SC, R> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(
             f,
-            "Genetic algorithm\nInitial pop size: {}",
-            self.initial_population.len()
+            "Genetic algorithm\nInitial pop size: {}\n{}",
+            self.initial_population.len(),
+            self.stop_criteria
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    use super::{selection::tournament, GeneticAlgorithm, GeneticCheckpoint, Replacement};
+    use crate::{
+        annealing::stop::{MaxEvaluations, MaxSteps},
+        base::{
+            solution_attr, Criterion, DerivedSolution, Evaluation, Event, OptAlgorithm, Problem,
+            Solution,
+        },
+        Result,
+    };
+
+    #[solution_attr]
+    #[derive(Clone, DerivedSolution)]
+    struct TestSolution {
+        x: f64,
+    }
+
+    #[derive(Clone, Copy)]
+    struct TestProblem;
+    impl Problem for TestProblem {}
+
+    fn penalty(_: &TestProblem, _: &TestSolution) -> f64 {
+        0.0
+    }
+
+    fn value(_: &TestProblem, solution: &TestSolution) -> f64 {
+        (solution.x - 3.0).powi(2)
+    }
+
+    fn change(population: &mut [TestSolution], rng: &mut StdRng) {
+        for specimen in population.iter_mut() {
+            specimen.x += rng.gen_range(-0.5..0.5);
+        }
+    }
+
+    fn select(
+        _: usize,
+        population: &Vec<TestSolution>,
+        rng: &mut StdRng,
+    ) -> Result<Vec<TestSolution>> {
+        tournament(
+            3,
+            population,
+            &Criterion::new(&penalty, &value, true),
+            rng,
+            0,
+        )
+    }
+
+    fn population() -> Vec<TestSolution> {
+        (0..20)
+            .map(|i| TestSolution {
+                x: i as f64 - 10.0,
+                eval: Evaluation::default(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn resume_continues_bit_for_bit() {
+        let mut criterion = Criterion::new(&penalty, &value, true);
+        let mut snapshots = vec![];
+        let mut save = |checkpoint: &GeneticCheckpoint<_, _, _>| snapshots.push(checkpoint.clone());
+        let mut genetic = GeneticAlgorithm::with_rng(
+            20,
+            population(),
+            &|population: &mut Vec<_>, rng: &mut _| change(population, rng),
+            &select,
+            MaxSteps::new(30),
+            None,
+            StdRng::seed_from_u64(5),
+        );
+        genetic.register_checkpoint(10, &mut save);
+        let uninterrupted = genetic.solve(TestProblem, &mut criterion).unwrap();
+        drop(genetic);
+
+        assert_eq!(3, snapshots.len());
+        let mut resumed = GeneticAlgorithm::with_rng(
+            20,
+            population(),
+            &|population: &mut Vec<_>, rng: &mut _| change(population, rng),
+            &select,
+            MaxSteps::new(30),
+            None,
+            StdRng::seed_from_u64(0),
+        );
+        let result = resumed
+            .resume(snapshots[0].clone(), TestProblem, &mut criterion)
+            .unwrap();
+
+        let xs = |population: &[TestSolution]| population.iter().map(|s| s.x).collect::<Vec<_>>();
+        assert_eq!(xs(&uninterrupted), xs(&result));
+    }
+
+    #[test]
+    fn stops_on_the_evaluation_budget() {
+        let mut criterion = Criterion::new(&penalty, &value, true);
+        let mut generations = 0;
+        let mut count = |event: &Event<TestSolution>| {
+            if let Event::IterationFinished(_) = event {
+                generations += 1;
+            }
+        };
+        let mut genetic = GeneticAlgorithm::with_rng(
+            20,
+            population(),
+            &|population: &mut Vec<_>, rng: &mut _| change(population, rng),
+            &select,
+            MaxEvaluations::new(100),
+            Some(&mut count),
+            StdRng::seed_from_u64(5),
+        );
+        genetic.solve(TestProblem, &mut criterion).unwrap();
+
+        // The initial population and 4 generations of offspring
+        assert_eq!(4, generations);
+    }
+
+    fn solve(replacement: Replacement) -> Vec<TestSolution> {
+        let mut criterion = Criterion::new(&penalty, &value, true);
+        let mut genetic = GeneticAlgorithm::with_rng(
+            20,
+            population(),
+            &|population: &mut Vec<_>, rng: &mut _| change(population, rng),
+            &select,
+            MaxSteps::new(5),
+            None,
+            StdRng::seed_from_u64(5),
+        );
+        genetic.set_replacement(replacement);
+
+        genetic.solve(TestProblem, &mut criterion).unwrap()
+    }
+
+    #[test]
+    fn elitism_keeps_the_best_specimen() {
+        // Initial population contains the optimum x = 3, every offspring is mutated
+        let best = &solve(Replacement::Generational { elite: 1 })[0];
+        assert_eq!(3.0, best.x);
+
+        let best = &solve(Replacement::Generational { elite: 0 })[0];
+        assert_ne!(3.0, best.x);
+    }
+
+    #[test]
+    fn steady_state_replaces_only_the_worst() {
+        let population = solve(Replacement::SteadyState { replaced: 2 });
+
+        assert_eq!(20, population.len());
+        assert_eq!(3.0, population[0].x);
+        for pair in population.windows(2) {
+            assert!(pair[0].get_value() <= pair[1].get_value());
+        }
+    }
+}
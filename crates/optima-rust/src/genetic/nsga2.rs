@@ -0,0 +1,299 @@
+use std::fmt::Display;
+
+use rand::{prelude::ThreadRng, thread_rng, Rng};
+
+use super::ChangePopFn;
+use crate::{
+    annealing::stop::StopCriteria,
+    base::{
+        crowding_distance, notify, Event, Iteration, MultiCriterion, MultiOptAlgorithm, Observer,
+        Problem, Solution,
+    },
+    Result,
+};
+
+/// NSGA-II: elitist genetic algorithm ranking the population by non-dominated fronts
+/// and crowding distance. Runs until `stop_criteria` stop, which are updated with the value of
+/// the first specimen of the first front after every generation.
+pub struct Nsga2<'a, S, SC, R = ThreadRng>
+where
+    S: Solution,
+    SC: StopCriteria,
+    R: Rng,
+{
+    pub population: Vec<S>,
+    pub change: &'a ChangePopFn<S, R>,
+    stop_criteria: SC,
+    initial_population: Vec<S>,
+    observer: Option<&'a mut dyn Observer<S>>,
+    rng: R,
+}
+
+impl<'a, S, SC> Nsga2<'a, S, SC, ThreadRng>
+where
+    S: Solution,
+    SC: StopCriteria,
+{
+    pub fn new(population: Vec<S>, change: &'a ChangePopFn<S>, stop_criteria: SC) -> Self {
+        Self::with_rng(population, change, stop_criteria, thread_rng())
+    }
+}
+
+impl<'a, S, SC, R> Nsga2<'a, S, SC, R>
+where
+    S: Solution,
+    SC: StopCriteria,
+    R: Rng,
+{
+    pub fn with_rng(
+        population: Vec<S>,
+        change: &'a ChangePopFn<S, R>,
+        stop_criteria: SC,
+        rng: R,
+    ) -> Self {
+        Self {
+            initial_population: population.clone(),
+            population,
+            change,
+            stop_criteria,
+            observer: None,
+            rng,
+        }
+    }
+
+    /// There is no single best specimen, events carry the first specimen of the first front.
+    pub fn register_observer(&mut self, observer: &'a mut dyn Observer<S>) {
+        self.observer = Some(observer);
+    }
+
+    /// Rank (index of the front) and crowding distance of every specimen.
+    fn rank<P: Problem>(&self, criterion: &MultiCriterion<P, S>) -> (Vec<usize>, Vec<f64>) {
+        let mut ranks = vec![0; self.population.len()];
+        let mut crowding = vec![0.0; self.population.len()];
+
+        for (rank, front) in criterion.fronts(&self.population).iter().enumerate() {
+            let distances = crowding_distance(&self.population, front);
+            for (k, &i) in front.iter().enumerate() {
+                ranks[i] = rank;
+                crowding[i] = distances[k];
+            }
+        }
+
+        (ranks, crowding)
+    }
+
+    /// Binary tournament on (rank, crowding distance).
+    fn select(&mut self, ranks: &[usize], crowding: &[f64]) -> Vec<S> {
+        let n = self.population.len();
+        let mut parents = Vec::with_capacity(n);
+
+        while parents.len() < n {
+            let a = self.rng.gen_range(0..n);
+            let b = self.rng.gen_range(0..n);
+            let winner = if ranks[a] != ranks[b] {
+                match ranks[a] < ranks[b] {
+                    true => a,
+                    false => b,
+                }
+            } else {
+                match crowding[a] >= crowding[b] {
+                    true => a,
+                    false => b,
+                }
+            };
+            parents.push(self.population[winner].clone());
+        }
+
+        parents
+    }
+
+    /// Fills the next population front by front, the last front that does not fit
+    /// is truncated by descending crowding distance.
+    fn survive<P: Problem>(&self, combined: Vec<S>, criterion: &MultiCriterion<P, S>) -> Vec<S> {
+        let n = self.population.len();
+        let mut next = Vec::with_capacity(n);
+
+        for front in criterion.fronts(&combined) {
+            if next.len() + front.len() <= n {
+                next.extend(front.iter().map(|&i| combined[i].clone()));
+                continue;
+            }
+
+            let distances = crowding_distance(&combined, &front);
+            let mut order: Vec<usize> = (0..front.len()).collect();
+            order.sort_by(|&a, &b| distances[b].total_cmp(&distances[a]));
+            for k in order.into_iter().take(n - next.len()) {
+                next.push(combined[front[k]].clone());
+            }
+            break;
+        }
+
+        next
+    }
+}
+
+impl<S, SC, P, R> MultiOptAlgorithm<'_, P, S> for Nsga2<'_, S, SC, R>
+where
+    S: Solution,
+    SC: StopCriteria,
+    P: Problem,
+    R: Rng,
+{
+    fn solve(&mut self, problem: P, criterion: &mut MultiCriterion<P, S>) -> Result<Vec<S>> {
+        self.stop_criteria.reset();
+        for specimen in self.population.iter_mut() {
+            criterion.evaluate(&problem, specimen);
+        }
+        self.stop_criteria.evaluated(self.population.len());
+
+        notify(&mut self.observer, &Event::RunStarted);
+
+        let mut generation = 0;
+        while !self.stop_criteria.should_stop() {
+            let (ranks, crowding) = self.rank(criterion);
+            let mut offspring = self.select(&ranks, &crowding);
+
+            (self.change)(&mut offspring, &mut self.rng);
+
+            for specimen in offspring.iter_mut() {
+                criterion.evaluate(&problem, specimen);
+            }
+            self.stop_criteria.evaluated(offspring.len());
+
+            let mut combined = self.population.clone();
+            combined.append(&mut offspring);
+            self.population = self.survive(combined, criterion);
+
+            if let Some(first) = self.population.first() {
+                notify(
+                    &mut self.observer,
+                    &Event::IterationFinished(Iteration {
+                        iteration: generation,
+                        best: first,
+                        current: first,
+                        temperature: None,
+                        diversity: None,
+                    }),
+                );
+                self.stop_criteria.update(first.get_eval());
+            }
+            generation += 1;
+        }
+
+        if let Some(first) = self.population.first() {
+            notify(
+                &mut self.observer,
+                &Event::RunFinished {
+                    iterations: generation,
+                    best: first,
+                },
+            );
+        }
+
+        Ok(criterion.non_dominated(&self.population))
+    }
+
+    fn reset(&mut self) {
+        self.population = self.initial_population.clone();
+        self.stop_criteria.reset();
+    }
+}
+
+impl<'a, S: Solution, SC: StopCriteria, R: Rng> Display for Nsga2<'a, S, SC, R> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "NSGA-II\nInitial pop size: {}\n{}",
+            self.initial_population.len(),
+            self.stop_criteria
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    use super::Nsga2;
+    use crate::annealing::stop::{MaxEvaluations, MaxSteps};
+    use crate::base::{
+        solution_attr, DerivedSolution, Evaluation, Event, MultiCriterion, MultiOptAlgorithm,
+        Problem, Solution,
+    };
+
+    #[solution_attr]
+    #[derive(Clone, DerivedSolution)]
+    struct TestSolution {
+        x: f64,
+    }
+
+    #[derive(Clone, Copy)]
+    struct TestProblem;
+    impl Problem for TestProblem {}
+
+    fn penalty(_: &TestProblem, _: &TestSolution) -> f64 {
+        0.0
+    }
+
+    fn f1(_: &TestProblem, solution: &TestSolution) -> f64 {
+        solution.x.powi(2)
+    }
+
+    fn f2(_: &TestProblem, solution: &TestSolution) -> f64 {
+        (solution.x - 2.0).powi(2)
+    }
+
+    fn change<R: Rng>(population: &mut [TestSolution], rng: &mut R) {
+        for specimen in population.iter_mut() {
+            specimen.x += rng.gen_range(-0.5..0.5);
+        }
+    }
+
+    #[test]
+    fn solve_converges_to_pareto_front() {
+        let mut rng = StdRng::seed_from_u64(3);
+        let population = (0..40)
+            .map(|_| TestSolution {
+                x: rng.gen_range(-10.0..10.0),
+                eval: Evaluation::default(),
+            })
+            .collect();
+
+        let mut criterion = MultiCriterion::new(&penalty, vec![(&f1, true), (&f2, true)]);
+        let change = |population: &mut Vec<_>, rng: &mut _| change(population, rng);
+        let mut nsga = Nsga2::with_rng(population, &change, MaxSteps::new(100), rng);
+
+        let front = nsga.solve(TestProblem, &mut criterion).unwrap();
+
+        assert!(front.len() > 1);
+        for solution in &front {
+            assert!(solution.x > -0.1 && solution.x < 2.1, "{}", solution.x);
+            assert_eq!(2, solution.get_eval().objectives.len());
+        }
+    }
+
+    #[test]
+    fn stops_on_the_evaluation_budget() {
+        let mut rng = StdRng::seed_from_u64(3);
+        let population = (0..10)
+            .map(|_| TestSolution {
+                x: rng.gen_range(-10.0..10.0),
+                eval: Evaluation::default(),
+            })
+            .collect();
+        let mut criterion = MultiCriterion::new(&penalty, vec![(&f1, true), (&f2, true)]);
+        let mut iterations = None;
+        let mut finished = |event: &Event<TestSolution>| {
+            if let Event::RunFinished { iterations: n, .. } = event {
+                iterations = Some(*n);
+            }
+        };
+        let change = |population: &mut Vec<_>, rng: &mut _| change(population, rng);
+        let mut nsga = Nsga2::with_rng(population, &change, MaxEvaluations::new(50), rng);
+        nsga.register_observer(&mut finished);
+
+        nsga.solve(TestProblem, &mut criterion).unwrap();
+
+        assert_eq!(Some(4), iterations);
+    }
+}
@@ -0,0 +1,181 @@
+use rand::Rng;
+
+use super::{Crossover, Mutation};
+use crate::base::Chromosome;
+
+/// Swaps the tails of the parents after a random cut point.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct OnePointCrossover;
+
+impl<S> Crossover<S> for OnePointCrossover
+where
+    S: Chromosome,
+    S::Gene: Clone,
+{
+    fn cross<R: Rng + ?Sized>(&self, first: &S, second: &S, rng: &mut R) -> (S, S) {
+        let len = first.genes().len().min(second.genes().len());
+        if len < 2 {
+            return (first.clone(), second.clone());
+        }
+
+        let cut = rng.gen_range(1..len);
+        swap_segment(first, second, cut, len)
+    }
+}
+
+/// Swaps the segment between two random cut points.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct TwoPointCrossover;
+
+impl<S> Crossover<S> for TwoPointCrossover
+where
+    S: Chromosome,
+    S::Gene: Clone,
+{
+    fn cross<R: Rng + ?Sized>(&self, first: &S, second: &S, rng: &mut R) -> (S, S) {
+        let len = first.genes().len().min(second.genes().len());
+        let a = rng.gen_range(0..=len);
+        let b = rng.gen_range(0..=len);
+
+        swap_segment(first, second, a.min(b), a.max(b))
+    }
+}
+
+/// Swaps every gene independently with `probability`.
+#[derive(Clone, Copy, Debug)]
+pub struct UniformCrossover {
+    probability: f64,
+}
+
+impl UniformCrossover {
+    pub fn new(probability: f64) -> Self {
+        Self { probability }
+    }
+}
+
+impl<S> Crossover<S> for UniformCrossover
+where
+    S: Chromosome,
+    S::Gene: Clone,
+{
+    fn cross<R: Rng + ?Sized>(&self, first: &S, second: &S, rng: &mut R) -> (S, S) {
+        let mut children = (first.clone(), second.clone());
+        let len = first.genes().len().min(second.genes().len());
+
+        for i in 0..len {
+            if rng.gen::<f64>() < self.probability {
+                children.0.genes_mut()[i] = second.genes()[i].clone();
+                children.1.genes_mut()[i] = first.genes()[i].clone();
+            }
+        }
+
+        children
+    }
+}
+
+/// Negates every bit independently with `probability`.
+#[derive(Clone, Copy, Debug)]
+pub struct BitFlip {
+    probability: f64,
+}
+
+impl BitFlip {
+    pub fn new(probability: f64) -> Self {
+        Self { probability }
+    }
+}
+
+impl<S> Mutation<S> for BitFlip
+where
+    S: Chromosome<Gene = bool>,
+{
+    fn mutate<R: Rng + ?Sized>(&self, specimen: &mut S, rng: &mut R) {
+        for gene in specimen.genes_mut() {
+            if rng.gen::<f64>() < self.probability {
+                *gene = !*gene;
+            }
+        }
+    }
+}
+
+fn swap_segment<S>(first: &S, second: &S, from: usize, to: usize) -> (S, S)
+where
+    S: Chromosome,
+    S::Gene: Clone,
+{
+    let mut children = (first.clone(), second.clone());
+    children.0.genes_mut()[from..to].clone_from_slice(&second.genes()[from..to]);
+    children.1.genes_mut()[from..to].clone_from_slice(&first.genes()[from..to]);
+
+    children
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use super::{BitFlip, OnePointCrossover, TwoPointCrossover, UniformCrossover};
+    use crate::{
+        base::{solution_attr, Chromosome, DerivedSolution, Evaluation},
+        genetic::operators::{Crossover, Mutation},
+    };
+
+    #[solution_attr]
+    #[derive(Clone, DerivedSolution)]
+    struct Bits {
+        genes: Vec<bool>,
+    }
+
+    impl Chromosome for Bits {
+        type Gene = bool;
+
+        fn genes(&self) -> &[bool] {
+            &self.genes
+        }
+
+        fn genes_mut(&mut self) -> &mut [bool] {
+            &mut self.genes
+        }
+    }
+
+    fn bits(value: bool) -> Bits {
+        Bits {
+            genes: vec![value; 12],
+            eval: Evaluation::default(),
+        }
+    }
+
+    // Every position holds one gene of each parent
+    fn assert_complementary(children: (Bits, Bits)) {
+        for (a, b) in children.0.genes.iter().zip(&children.1.genes) {
+            assert_ne!(a, b);
+        }
+    }
+
+    #[test]
+    fn crossovers_exchange_genes() {
+        let mut rng = StdRng::seed_from_u64(2);
+        let (ones, zeros) = (bits(true), bits(false));
+
+        for _ in 0..20 {
+            let children = OnePointCrossover.cross(&ones, &zeros, &mut rng);
+            assert!(children.0.genes[0] && !children.0.genes[11]);
+            assert_complementary(children);
+
+            assert_complementary(TwoPointCrossover.cross(&ones, &zeros, &mut rng));
+            assert_complementary(UniformCrossover::new(0.5).cross(&ones, &zeros, &mut rng));
+        }
+    }
+
+    #[test]
+    fn bit_flip_with_certainty_negates_everything() {
+        let mut rng = StdRng::seed_from_u64(2);
+        let mut specimen = bits(true);
+
+        BitFlip::new(1.0).mutate(&mut specimen, &mut rng);
+        assert!(specimen.genes.iter().all(|gene| !gene));
+
+        BitFlip::new(0.0).mutate(&mut specimen, &mut rng);
+        assert!(specimen.genes.iter().all(|gene| !gene));
+    }
+}
@@ -0,0 +1,177 @@
+use rand::Rng;
+
+use super::{real::standard_normal, Crossover, Mutation};
+use crate::base::{Encoding, GeneKind, Solution};
+
+/// Swaps every gene of the children with `probability`, for solutions deriving `Encoding`.
+#[derive(Clone, Copy, Debug)]
+pub struct UniformCrossover {
+    probability: f64,
+}
+
+impl UniformCrossover {
+    pub fn new(probability: f64) -> Self {
+        Self { probability }
+    }
+}
+
+impl<S: Solution + Encoding> Crossover<S> for UniformCrossover {
+    fn cross<R: Rng + ?Sized>(&self, first: &S, second: &S, rng: &mut R) -> (S, S) {
+        let (mut genes1, mut genes2) = (first.to_vec(), second.to_vec());
+        for (x1, x2) in genes1.iter_mut().zip(genes2.iter_mut()) {
+            if rng.gen::<f64>() < self.probability {
+                std::mem::swap(x1, x2);
+            }
+        }
+
+        let mut children = (first.clone(), second.clone());
+        children.0.set_from_slice(&genes1);
+        children.1.set_from_slice(&genes2);
+        children
+    }
+}
+
+/// Mutates every gene independently with `probability`. Real and integer genes get normally
+/// distributed noise with standard deviation `sigma` times the width of their range, boolean
+/// genes are flipped. Mutated genes stay within their ranges.
+#[derive(Clone, Copy, Debug)]
+pub struct GaussianMutation {
+    sigma: f64,
+    probability: f64,
+}
+
+impl GaussianMutation {
+    pub fn new(sigma: f64, probability: f64) -> Self {
+        Self { sigma, probability }
+    }
+}
+
+impl<S: Solution + Encoding> Mutation<S> for GaussianMutation {
+    fn mutate<R: Rng + ?Sized>(&self, specimen: &mut S, rng: &mut R) {
+        let mut values = specimen.to_vec();
+        for (value, gene) in values.iter_mut().zip(S::genes()) {
+            if rng.gen::<f64>() >= self.probability {
+                continue;
+            }
+
+            *value = match gene.kind {
+                GeneKind::Boolean => 1.0 - *value,
+                GeneKind::Real | GeneKind::Integer => {
+                    let width = gene.range.end() - gene.range.start();
+                    *value + self.sigma * width * standard_normal(rng)
+                }
+            };
+        }
+        specimen.set_from_slice(&values);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use super::{GaussianMutation, UniformCrossover};
+    use crate::{
+        annealing::stop::MaxSteps,
+        base::{
+            solution_attr, Criterion, DerivedSolution, Encoding, OptAlgorithm, Problem, Solution,
+        },
+        genetic::{
+            operators::{breed, Crossover, Mutation},
+            selection::tournament,
+            GeneticAlgorithm, Replacement,
+        },
+        Result,
+    };
+
+    #[solution_attr]
+    #[derive(Clone, DerivedSolution, Encoding)]
+    struct Design {
+        #[gene(range = -4.0..=4.0)]
+        x: f64,
+        #[gene(range = 0..=10)]
+        n: i32,
+        #[gene]
+        enabled: bool,
+    }
+
+    #[derive(Clone, Copy)]
+    struct DesignProblem;
+    impl Problem for DesignProblem {}
+
+    fn penalty(_: &DesignProblem, _: &Design) -> f64 {
+        0.0
+    }
+
+    fn value(_: &DesignProblem, design: &Design) -> f64 {
+        (design.x - 1.5).powi(2)
+            + (design.n - 7).abs() as f64
+            + f64::from(u8::from(!design.enabled))
+    }
+
+    #[test]
+    fn crossover_keeps_genes_in_place() {
+        let mut rng = StdRng::seed_from_u64(22);
+        let first = Design::from_slice(&[-1.0, 2.0, 0.0]);
+        let second = Design::from_slice(&[3.0, 8.0, 1.0]);
+
+        for _ in 0..20 {
+            let (a, b) = UniformCrossover::new(0.5).cross(&first, &second, &mut rng);
+            for (i, (x, y)) in a.to_vec().into_iter().zip(b.to_vec()).enumerate() {
+                let parents = (first.to_vec()[i], second.to_vec()[i]);
+                assert!((x, y) == parents || (y, x) == parents);
+            }
+        }
+    }
+
+    #[test]
+    fn mutation_stays_in_bounds() {
+        let mut rng = StdRng::seed_from_u64(22);
+        let mut design = Design::from_slice(&[4.0, 10.0, 0.0]);
+
+        GaussianMutation::new(0.0, 1.0).mutate(&mut design, &mut rng);
+        assert!(design.enabled);
+
+        for _ in 0..50 {
+            GaussianMutation::new(1.0, 1.0).mutate(&mut design, &mut rng);
+            assert!((-4.0..=4.0).contains(&design.x));
+            assert!((0..=10).contains(&design.n));
+        }
+    }
+
+    #[test]
+    fn genetic_algorithm_solves_derived_encoding() {
+        let change = |population: &mut Vec<Design>, rng: &mut StdRng| {
+            let mutation = GaussianMutation::new(0.1, 0.3);
+            breed(population, &UniformCrossover::new(0.5), 0.9, &mutation, rng)
+        };
+
+        fn select(_: usize, population: &Vec<Design>, rng: &mut StdRng) -> Result<Vec<Design>> {
+            tournament(
+                3,
+                population,
+                &Criterion::new(&penalty, &value, true),
+                rng,
+                0,
+            )
+        }
+
+        let mut rng = StdRng::seed_from_u64(22);
+        let population = (0..30).map(|_| Design::random_init(&mut rng)).collect();
+        let mut criterion = Criterion::new(&penalty, &value, true);
+        let mut genetic = GeneticAlgorithm::with_rng(
+            30,
+            population,
+            &change,
+            &select,
+            MaxSteps::new(60),
+            None,
+            rng,
+        );
+        genetic.set_replacement(Replacement::Generational { elite: 2 });
+        let best = &genetic.solve(DesignProblem, &mut criterion).unwrap()[0];
+
+        assert!(best.get_value() < 0.1, "best value {}", best.get_value());
+        assert_eq!((7, true), (best.n, best.enabled));
+    }
+}
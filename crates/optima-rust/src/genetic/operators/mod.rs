@@ -0,0 +1,112 @@
+use rand::Rng;
+
+use crate::base::Solution;
+
+pub mod binary;
+pub mod encoded;
+pub mod permutation;
+pub mod real;
+
+pub trait Crossover<S: Solution> {
+    /// Produces two children from two parents. Children carry the parents' stale evaluation.
+    fn cross<R: Rng + ?Sized>(&self, first: &S, second: &S, rng: &mut R) -> (S, S);
+}
+
+pub trait Mutation<S: Solution> {
+    fn mutate<R: Rng + ?Sized>(&self, specimen: &mut S, rng: &mut R);
+}
+
+/// Replaces the population with its offspring: random parents are crossed with probability
+/// `crossover_rate` (copied otherwise) and every child is mutated. Meant to be called from a
+/// `ChangePopFn`.
+pub fn breed<S, C, M, R>(
+    population: &mut Vec<S>,
+    crossover: &C,
+    crossover_rate: f64,
+    mutation: &M,
+    rng: &mut R,
+) where
+    S: Solution,
+    C: Crossover<S>,
+    M: Mutation<S>,
+    R: Rng + ?Sized,
+{
+    if population.is_empty() {
+        return;
+    }
+
+    let mut children = Vec::with_capacity(population.len());
+    while children.len() < population.len() {
+        let father = &population[rng.gen_range(0..population.len())];
+        let mother = &population[rng.gen_range(0..population.len())];
+
+        let (first, second) = match rng.gen::<f64>() < crossover_rate {
+            true => crossover.cross(father, mother, rng),
+            false => (father.clone(), mother.clone()),
+        };
+
+        children.push(first);
+        if children.len() < population.len() {
+            children.push(second);
+        }
+    }
+
+    for child in children.iter_mut() {
+        mutation.mutate(child, rng);
+    }
+
+    *population = children;
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use super::{
+        binary::{BitFlip, OnePointCrossover},
+        breed,
+    };
+    use crate::base::{solution_attr, Chromosome, DerivedSolution, Evaluation};
+
+    #[solution_attr]
+    #[derive(Clone, DerivedSolution)]
+    struct Bits {
+        genes: Vec<bool>,
+    }
+
+    impl Chromosome for Bits {
+        type Gene = bool;
+
+        fn genes(&self) -> &[bool] {
+            &self.genes
+        }
+
+        fn genes_mut(&mut self) -> &mut [bool] {
+            &mut self.genes
+        }
+    }
+
+    #[test]
+    fn breed_keeps_population_size() {
+        let mut rng = StdRng::seed_from_u64(1);
+        let mut population: Vec<Bits> = (0..7)
+            .map(|i| Bits {
+                genes: vec![i % 2 == 0; 10],
+                eval: Evaluation::default(),
+            })
+            .collect();
+
+        breed(
+            &mut population,
+            &OnePointCrossover,
+            1.0,
+            &BitFlip::new(0.0),
+            &mut rng,
+        );
+
+        assert_eq!(7, population.len());
+        assert!(population
+            .iter()
+            .all(|specimen| specimen.genes().len() == 10));
+    }
+}
@@ -0,0 +1,287 @@
+use rand::Rng;
+
+use super::{Crossover, Mutation};
+use crate::base::Chromosome;
+
+/// PMX: each child keeps a random segment of one parent, the remaining genes come from the other
+/// parent, with duplicates resolved through the mapping defined by the segment.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct PartiallyMappedCrossover;
+
+impl<S> Crossover<S> for PartiallyMappedCrossover
+where
+    S: Chromosome,
+    S::Gene: Copy + PartialEq,
+{
+    fn cross<R: Rng + ?Sized>(&self, first: &S, second: &S, rng: &mut R) -> (S, S) {
+        let (from, to) = random_segment(first.genes().len(), rng);
+
+        let mut children = (first.clone(), second.clone());
+        pmx(
+            first.genes(),
+            second.genes(),
+            from,
+            to,
+            children.0.genes_mut(),
+        );
+        pmx(
+            second.genes(),
+            first.genes(),
+            from,
+            to,
+            children.1.genes_mut(),
+        );
+
+        children
+    }
+}
+
+/// OX: each child keeps a random segment of one parent and gets the remaining genes in the order
+/// they appear in the other parent, starting after the segment.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct OrderCrossover;
+
+impl<S> Crossover<S> for OrderCrossover
+where
+    S: Chromosome,
+    S::Gene: Copy + PartialEq,
+{
+    fn cross<R: Rng + ?Sized>(&self, first: &S, second: &S, rng: &mut R) -> (S, S) {
+        let (from, to) = random_segment(first.genes().len(), rng);
+
+        let mut children = (first.clone(), second.clone());
+        ox(
+            first.genes(),
+            second.genes(),
+            from,
+            to,
+            children.0.genes_mut(),
+        );
+        ox(
+            second.genes(),
+            first.genes(),
+            from,
+            to,
+            children.1.genes_mut(),
+        );
+
+        children
+    }
+}
+
+/// CX: splits the positions into cycles and takes alternate cycles from each parent,
+/// so every gene keeps the position it has in one of the parents.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct CycleCrossover;
+
+impl<S> Crossover<S> for CycleCrossover
+where
+    S: Chromosome,
+    S::Gene: Copy + PartialEq,
+{
+    fn cross<R: Rng + ?Sized>(&self, first: &S, second: &S, _rng: &mut R) -> (S, S) {
+        let (a, b) = (first.genes(), second.genes());
+        let mut children = (first.clone(), second.clone());
+        let mut visited = vec![false; a.len()];
+        let mut from_first = true;
+
+        for start in 0..a.len() {
+            if visited[start] {
+                continue;
+            }
+
+            let mut i = start;
+            while !visited[i] {
+                visited[i] = true;
+                let (x, y) = match from_first {
+                    true => (a[i], b[i]),
+                    false => (b[i], a[i]),
+                };
+                children.0.genes_mut()[i] = x;
+                children.1.genes_mut()[i] = y;
+
+                i = match position(a, b[i]) {
+                    Some(next) => next,
+                    None => break,
+                };
+            }
+            from_first = !from_first;
+        }
+
+        children
+    }
+}
+
+/// Swaps two random genes with `probability`.
+#[derive(Clone, Copy, Debug)]
+pub struct SwapMutation {
+    probability: f64,
+}
+
+impl SwapMutation {
+    pub fn new(probability: f64) -> Self {
+        Self { probability }
+    }
+}
+
+impl<S: Chromosome> Mutation<S> for SwapMutation {
+    fn mutate<R: Rng + ?Sized>(&self, specimen: &mut S, rng: &mut R) {
+        let len = specimen.genes().len();
+        if len < 2 || rng.gen::<f64>() >= self.probability {
+            return;
+        }
+
+        let i = rng.gen_range(0..len);
+        let j = rng.gen_range(0..len);
+        specimen.genes_mut().swap(i, j);
+    }
+}
+
+/// Reverses a random segment with `probability`.
+#[derive(Clone, Copy, Debug)]
+pub struct InversionMutation {
+    probability: f64,
+}
+
+impl InversionMutation {
+    pub fn new(probability: f64) -> Self {
+        Self { probability }
+    }
+}
+
+impl<S: Chromosome> Mutation<S> for InversionMutation {
+    fn mutate<R: Rng + ?Sized>(&self, specimen: &mut S, rng: &mut R) {
+        let len = specimen.genes().len();
+        if len < 2 || rng.gen::<f64>() >= self.probability {
+            return;
+        }
+
+        let (from, to) = random_segment(len, rng);
+        specimen.genes_mut()[from..to].reverse();
+    }
+}
+
+fn random_segment<R: Rng + ?Sized>(len: usize, rng: &mut R) -> (usize, usize) {
+    let a = rng.gen_range(0..=len);
+    let b = rng.gen_range(0..=len);
+    (a.min(b), a.max(b))
+}
+
+fn position<G: PartialEq>(genes: &[G], gene: G) -> Option<usize> {
+    genes.iter().position(|g| *g == gene)
+}
+
+fn pmx<G: Copy + PartialEq>(keep: &[G], other: &[G], from: usize, to: usize, child: &mut [G]) {
+    let segment = &keep[from..to];
+    for i in (0..from).chain(to..other.len()) {
+        let mut gene = other[i];
+        while let Some(k) = segment.iter().position(|g| *g == gene) {
+            gene = other[from + k];
+        }
+        child[i] = gene;
+    }
+    child[from..to].copy_from_slice(segment);
+}
+
+fn ox<G: Copy + PartialEq>(keep: &[G], other: &[G], from: usize, to: usize, child: &mut [G]) {
+    let len = other.len();
+    let segment = &keep[from..to];
+    child[from..to].copy_from_slice(segment);
+
+    let mut target = to % len.max(1);
+    for k in 0..len {
+        let gene = other[(to + k) % len];
+        if segment.contains(&gene) {
+            continue;
+        }
+        child[target] = gene;
+        target = (target + 1) % len;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use super::{
+        CycleCrossover, InversionMutation, OrderCrossover, PartiallyMappedCrossover, SwapMutation,
+    };
+    use crate::{
+        base::{solution_attr, Chromosome, DerivedSolution, Evaluation},
+        genetic::operators::{Crossover, Mutation},
+    };
+
+    #[solution_attr]
+    #[derive(Clone, DerivedSolution)]
+    struct Tour {
+        cities: Vec<usize>,
+    }
+
+    impl Chromosome for Tour {
+        type Gene = usize;
+
+        fn genes(&self) -> &[usize] {
+            &self.cities
+        }
+
+        fn genes_mut(&mut self) -> &mut [usize] {
+            &mut self.cities
+        }
+    }
+
+    fn tour(cities: Vec<usize>) -> Tour {
+        Tour {
+            cities,
+            eval: Evaluation::default(),
+        }
+    }
+
+    fn assert_permutation(tour: &Tour) {
+        let mut cities = tour.cities.clone();
+        cities.sort();
+        assert_eq!((0..9).collect::<Vec<_>>(), cities);
+    }
+
+    #[test]
+    fn crossovers_produce_permutations() {
+        let mut rng = StdRng::seed_from_u64(4);
+        let first = tour(vec![0, 1, 2, 3, 4, 5, 6, 7, 8]);
+        let second = tour(vec![8, 2, 6, 7, 1, 5, 4, 0, 3]);
+
+        for _ in 0..50 {
+            for (a, b) in [
+                PartiallyMappedCrossover.cross(&first, &second, &mut rng),
+                OrderCrossover.cross(&first, &second, &mut rng),
+                CycleCrossover.cross(&first, &second, &mut rng),
+            ] {
+                assert_permutation(&a);
+                assert_permutation(&b);
+            }
+        }
+    }
+
+    #[test]
+    fn cycle_crossover_keeps_positions() {
+        let mut rng = StdRng::seed_from_u64(4);
+        let first = tour(vec![0, 1, 2, 3, 4, 5, 6, 7, 8]);
+        let second = tour(vec![8, 2, 6, 7, 1, 5, 4, 0, 3]);
+
+        let (a, _) = CycleCrossover.cross(&first, &second, &mut rng);
+        for i in 0..9 {
+            assert!(a.cities[i] == first.cities[i] || a.cities[i] == second.cities[i]);
+        }
+    }
+
+    #[test]
+    fn mutations_keep_permutations() {
+        let mut rng = StdRng::seed_from_u64(4);
+        let mut specimen = tour((0..9).collect());
+
+        for _ in 0..50 {
+            SwapMutation::new(1.0).mutate(&mut specimen, &mut rng);
+            InversionMutation::new(1.0).mutate(&mut specimen, &mut rng);
+            assert_permutation(&specimen);
+        }
+        assert_ne!((0..9).collect::<Vec<_>>(), specimen.cities);
+    }
+}
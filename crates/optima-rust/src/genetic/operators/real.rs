@@ -0,0 +1,221 @@
+use std::{f64::consts::PI, ops::RangeInclusive};
+
+use rand::Rng;
+
+use super::{Crossover, Mutation};
+use crate::base::Chromosome;
+
+/// SBX: simulated binary crossover with distribution index `eta`, larger values keep the
+/// children closer to their parents.
+#[derive(Clone, Copy, Debug)]
+pub struct SimulatedBinaryCrossover {
+    eta: f64,
+}
+
+impl SimulatedBinaryCrossover {
+    pub fn new(eta: f64) -> Self {
+        Self { eta }
+    }
+}
+
+impl<S: Chromosome<Gene = f64>> Crossover<S> for SimulatedBinaryCrossover {
+    fn cross<R: Rng + ?Sized>(&self, first: &S, second: &S, rng: &mut R) -> (S, S) {
+        let mut children = (first.clone(), second.clone());
+        let exponent = 1.0 / (self.eta + 1.0);
+
+        for (i, (x1, x2)) in first.genes().iter().zip(second.genes()).enumerate() {
+            let u: f64 = rng.gen();
+            let beta = match u <= 0.5 {
+                true => (2.0 * u).powf(exponent),
+                false => (1.0 / (2.0 * (1.0 - u))).powf(exponent),
+            };
+
+            children.0.genes_mut()[i] = 0.5 * ((1.0 + beta) * x1 + (1.0 - beta) * x2);
+            children.1.genes_mut()[i] = 0.5 * ((1.0 - beta) * x1 + (1.0 + beta) * x2);
+        }
+
+        children
+    }
+}
+
+/// BLX-alpha: every child gene is drawn uniformly from the parents' interval
+/// extended by `alpha` times its width on both sides.
+#[derive(Clone, Copy, Debug)]
+pub struct BlendCrossover {
+    alpha: f64,
+}
+
+impl BlendCrossover {
+    pub fn new(alpha: f64) -> Self {
+        Self { alpha }
+    }
+}
+
+impl<S: Chromosome<Gene = f64>> Crossover<S> for BlendCrossover {
+    fn cross<R: Rng + ?Sized>(&self, first: &S, second: &S, rng: &mut R) -> (S, S) {
+        let mut children = (first.clone(), second.clone());
+
+        for (i, (x1, x2)) in first.genes().iter().zip(second.genes()).enumerate() {
+            let (min, max) = (x1.min(*x2), x1.max(*x2));
+            let extension = self.alpha * (max - min);
+            let range = (min - extension)..=(max + extension);
+
+            children.0.genes_mut()[i] = sample(range.clone(), rng);
+            children.1.genes_mut()[i] = sample(range, rng);
+        }
+
+        children
+    }
+}
+
+/// Adds normally distributed noise with standard deviation `sigma` to every gene
+/// independently with `probability`.
+#[derive(Clone, Copy, Debug)]
+pub struct GaussianMutation {
+    sigma: f64,
+    probability: f64,
+}
+
+impl GaussianMutation {
+    pub fn new(sigma: f64, probability: f64) -> Self {
+        Self { sigma, probability }
+    }
+}
+
+impl<S: Chromosome<Gene = f64>> Mutation<S> for GaussianMutation {
+    fn mutate<R: Rng + ?Sized>(&self, specimen: &mut S, rng: &mut R) {
+        for gene in specimen.genes_mut() {
+            if rng.gen::<f64>() < self.probability {
+                *gene += self.sigma * standard_normal(rng);
+            }
+        }
+    }
+}
+
+/// Deb's polynomial mutation with distribution index `eta`, mutated genes stay within `bounds`
+/// (one range per gene).
+#[derive(Clone, Debug)]
+pub struct PolynomialMutation {
+    eta: f64,
+    probability: f64,
+    bounds: Vec<RangeInclusive<f64>>,
+}
+
+impl PolynomialMutation {
+    pub fn new(eta: f64, probability: f64, bounds: Vec<RangeInclusive<f64>>) -> Self {
+        Self {
+            eta,
+            probability,
+            bounds,
+        }
+    }
+}
+
+impl<S: Chromosome<Gene = f64>> Mutation<S> for PolynomialMutation {
+    fn mutate<R: Rng + ?Sized>(&self, specimen: &mut S, rng: &mut R) {
+        let exponent = 1.0 / (self.eta + 1.0);
+
+        for (gene, range) in specimen.genes_mut().iter_mut().zip(&self.bounds) {
+            if rng.gen::<f64>() >= self.probability {
+                continue;
+            }
+
+            let u: f64 = rng.gen();
+            let delta = match u < 0.5 {
+                true => (2.0 * u).powf(exponent) - 1.0,
+                false => 1.0 - (2.0 * (1.0 - u)).powf(exponent),
+            };
+            let (min, max) = (*range.start(), *range.end());
+            *gene = f64::clamp(*gene + delta * (max - min), min, max);
+        }
+    }
+}
+
+fn sample<R: Rng + ?Sized>(range: RangeInclusive<f64>, rng: &mut R) -> f64 {
+    match range.start() < range.end() {
+        true => rng.gen_range(range),
+        false => *range.start(),
+    }
+}
+
+// Box-Muller transform
+pub(super) fn standard_normal<R: Rng + ?Sized>(rng: &mut R) -> f64 {
+    let u1 = 1.0 - rng.gen::<f64>();
+    let u2: f64 = rng.gen();
+    (-2.0 * u1.ln()).sqrt() * (2.0 * PI * u2).cos()
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use super::{BlendCrossover, GaussianMutation, PolynomialMutation, SimulatedBinaryCrossover};
+    use crate::{
+        base::{solution_attr, Chromosome, DerivedSolution, Evaluation},
+        genetic::operators::{Crossover, Mutation},
+    };
+
+    #[solution_attr]
+    #[derive(Clone, DerivedSolution)]
+    struct Point {
+        coordinates: Vec<f64>,
+    }
+
+    impl Chromosome for Point {
+        type Gene = f64;
+
+        fn genes(&self) -> &[f64] {
+            &self.coordinates
+        }
+
+        fn genes_mut(&mut self) -> &mut [f64] {
+            &mut self.coordinates
+        }
+    }
+
+    fn point(coordinates: Vec<f64>) -> Point {
+        Point {
+            coordinates,
+            eval: Evaluation::default(),
+        }
+    }
+
+    #[test]
+    fn simulated_binary_crossover_preserves_mean() {
+        let mut rng = StdRng::seed_from_u64(6);
+        let (first, second) = (point(vec![1.0, -2.0]), point(vec![3.0, 4.0]));
+
+        for _ in 0..20 {
+            let (a, b) = SimulatedBinaryCrossover::new(2.0).cross(&first, &second, &mut rng);
+            assert!((a.coordinates[0] + b.coordinates[0] - 4.0).abs() < 1e-9);
+            assert!((a.coordinates[1] + b.coordinates[1] - 2.0).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn blend_crossover_stays_in_extended_interval() {
+        let mut rng = StdRng::seed_from_u64(6);
+        let (first, second) = (point(vec![0.0, 5.0]), point(vec![2.0, 5.0]));
+
+        for _ in 0..50 {
+            let (a, _) = BlendCrossover::new(0.5).cross(&first, &second, &mut rng);
+            assert!(a.coordinates[0] >= -1.0 && a.coordinates[0] <= 3.0);
+            assert_eq!(5.0, a.coordinates[1]);
+        }
+    }
+
+    #[test]
+    fn mutations_move_genes() {
+        let mut rng = StdRng::seed_from_u64(6);
+        let mut specimen = point(vec![0.0; 5]);
+
+        GaussianMutation::new(1.0, 1.0).mutate(&mut specimen, &mut rng);
+        assert!(specimen.coordinates.iter().all(|x| *x != 0.0));
+
+        let polynomial = PolynomialMutation::new(20.0, 1.0, vec![-0.1..=0.1; 5]);
+        for _ in 0..20 {
+            polynomial.mutate(&mut specimen, &mut rng);
+            assert!(specimen.coordinates.iter().all(|x| x.abs() <= 0.1));
+        }
+    }
+}
@@ -1,15 +1,23 @@
 use rand::{
     distributions::{Uniform, WeightedIndex},
-    prelude::{Distribution, ThreadRng},
+    prelude::Distribution,
+    Rng,
 };
 
-use crate::base::Solution;
+use crate::{
+    base::{Criterion, Problem, Solution},
+    Error, Result,
+};
 
-pub fn roulette<S: Solution>(
+pub fn roulette<S: Solution, R: Rng + ?Sized>(
     population: &Vec<S>,
     is_minimization: bool,
-    rng: &mut ThreadRng,
-) -> Vec<S> {
+    rng: &mut R,
+) -> Result<Vec<S>> {
+    if population.is_empty() {
+        return Err(Error::EmptyPopulation);
+    }
+
     let mut new_population = Vec::with_capacity(population.len());
     let mut weights = Vec::with_capacity(population.len());
 
@@ -41,49 +49,159 @@ pub fn roulette<S: Solution>(
             weights.push(0.0);
         }
     }
-    let uniform = WeightedIndex::new(vec![1.0; population.len()]).unwrap();
-    let dist = WeightedIndex::new(&weights).unwrap_or(uniform);
+    let dist = match WeightedIndex::new(&weights) {
+        Ok(dist) => dist,
+        Err(_) => WeightedIndex::new(vec![1.0; population.len()])?,
+    };
     while new_population.len() < population.len() {
         let index = dist.sample(rng);
         let specimen = population[index].clone();
         new_population.push(specimen);
     }
 
-    new_population
+    Ok(new_population)
 }
 
-pub fn tournament<S: Solution>(
+/// Fills the population with the winners of tournaments between `tournament_size` random
+/// specimens, after the `keep_elite` best specimens. Specimens are ranked by
+/// `Criterion::is_first_better`, usually of a clone of the criterion the algorithm runs with.
+pub fn tournament<P: Problem, S: Solution, R: Rng + ?Sized>(
     tournament_size: u16,
     population: &Vec<S>,
-    is_minimization: bool,
-    rng: &mut ThreadRng,
-    _keep_elite: u8,
-) -> Vec<S> {
+    criterion: &Criterion<P, S>,
+    rng: &mut R,
+    keep_elite: u8,
+) -> Result<Vec<S>> {
+    if population.is_empty() {
+        return Err(Error::EmptyPopulation);
+    }
+
     let mut new_population = Vec::with_capacity(population.len());
+
+    //Best specimens pass without a tournament, NaN values are never elite
+    if keep_elite > 0 {
+        let mut ranked: Vec<S> = population
+            .iter()
+            .filter(|specimen| !specimen.get_value().is_nan())
+            .cloned()
+            .collect();
+        criterion.sort(&mut ranked)?;
+        new_population.extend(ranked.into_iter().take(keep_elite as usize));
+    }
+
     let dist = Uniform::new(0, population.len());
 
     while new_population.len() < population.len() {
-        let mut best_knight_index = 0;
-        let mut best_knight_value = population[0].get_value();
-        //Tournament begins
-        for _ in 0..tournament_size {
+        //Tournament begins, the first knight drawn is the one to beat
+        let mut best_knight_index = dist.sample(rng);
+        for _ in 1..tournament_size {
             let opponent_index = dist.sample(rng);
-            let opponent_value = population[opponent_index].get_value();
-
-            if is_minimization {
-                if opponent_value < best_knight_value {
-                    best_knight_value = opponent_value;
-                    best_knight_index = opponent_index;
-                }
-            } else {
-                if opponent_value > best_knight_value {
-                    best_knight_value = opponent_value;
-                    best_knight_index = opponent_index;
-                }
+            if criterion.is_first_better(
+                population[opponent_index].get_eval(),
+                population[best_knight_index].get_eval(),
+            ) {
+                best_knight_index = opponent_index;
             }
         }
         new_population.push(population[best_knight_index].clone());
     }
 
-    new_population
+    Ok(new_population)
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use super::tournament;
+    use crate::base::{solution_attr, Criterion, DerivedSolution, Evaluation, Problem, Solution};
+
+    #[solution_attr]
+    #[derive(Clone, DerivedSolution)]
+    struct TestSolution {}
+
+    #[derive(Clone, Copy)]
+    struct TestProblem;
+    impl Problem for TestProblem {}
+
+    fn zero(_: &TestProblem, _: &TestSolution) -> f64 {
+        0.0
+    }
+
+    fn with_value(value: f64) -> TestSolution {
+        TestSolution {
+            eval: Evaluation {
+                value,
+                is_feasible: true,
+                ..Default::default()
+            },
+        }
+    }
+
+    fn infeasible(violation: f64) -> TestSolution {
+        TestSolution {
+            eval: Evaluation {
+                value: violation,
+                is_feasible: false,
+                violation,
+                ..Default::default()
+            },
+        }
+    }
+
+    #[test]
+    fn tournament_keeps_elite_first() {
+        let population = vec![
+            with_value(5.0),
+            with_value(f64::NAN),
+            with_value(1.0),
+            with_value(3.0),
+        ];
+        let mut rng = StdRng::seed_from_u64(1);
+
+        let minimization = Criterion::new(&zero, &zero, true);
+        let selected = tournament(2, &population, &minimization, &mut rng, 2).unwrap();
+        assert_eq!(4, selected.len());
+        assert_eq!(1.0, selected[0].get_value());
+        assert_eq!(3.0, selected[1].get_value());
+
+        let maximization = Criterion::new(&zero, &zero, false);
+        let selected = tournament(2, &population, &maximization, &mut rng, 1).unwrap();
+        assert_eq!(5.0, selected[0].get_value());
+    }
+
+    #[test]
+    fn tournament_elite_is_feasible_first() {
+        let population = vec![with_value(5.0), infeasible(0.5), with_value(3.0)];
+        let mut rng = StdRng::seed_from_u64(1);
+        let criterion = Criterion::new(&zero, &zero, true);
+
+        let selected = tournament(2, &population, &criterion, &mut rng, 2).unwrap();
+        assert!(selected[0].get_eval().is_feasible && selected[1].get_eval().is_feasible);
+        assert_eq!(3.0, selected[0].get_value());
+        assert_eq!(5.0, selected[1].get_value());
+    }
+
+    #[test]
+    fn tournament_of_one_picks_any_specimen() {
+        let population = vec![with_value(1.0), with_value(2.0), with_value(3.0)];
+        let mut rng = StdRng::seed_from_u64(1);
+        let criterion = Criterion::new(&zero, &zero, true);
+
+        let mut picked = vec![];
+        for _ in 0..10 {
+            picked.extend(tournament(1, &population, &criterion, &mut rng, 0).unwrap());
+        }
+        for value in [1.0, 2.0, 3.0] {
+            assert!(picked.iter().any(|specimen| specimen.get_value() == value));
+        }
+    }
+
+    #[test]
+    fn tournament_rejects_empty_population() {
+        let population: Vec<TestSolution> = vec![];
+        let mut rng = StdRng::seed_from_u64(1);
+        let criterion = Criterion::new(&zero, &zero, true);
+        assert!(tournament(2, &population, &criterion, &mut rng, 0).is_err());
+    }
 }
@@ -0,0 +1,3 @@
+//! Readers and writers of standard benchmark instance formats.
+
+pub mod tsplib;
@@ -0,0 +1,490 @@
+use std::{
+    fs::{self, File},
+    io::{BufWriter, Write},
+    path::Path,
+};
+
+use crate::{permutation::Distances, Error, Result};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EdgeWeightType {
+    Euc2d,
+    Geo,
+    Att,
+    Explicit,
+}
+
+/// Instance read from a TSPLIB `.tsp` file. Nodes are numbered from zero.
+#[derive(Clone, Debug)]
+pub struct TsplibInstance {
+    pub name: String,
+    pub comment: Option<String>,
+    pub dimension: usize,
+    pub edge_weight_type: EdgeWeightType,
+    /// Empty for `EXPLICIT` instances
+    pub coordinates: Vec<(f64, f64)>,
+    /// Full matrix for `EXPLICIT` instances, empty otherwise
+    pub weights: Vec<Vec<f64>>,
+}
+
+impl TsplibInstance {
+    /// Precomputes all distances, worth it when they are queried many times.
+    pub fn distance_matrix(&self) -> Vec<Vec<f64>> {
+        (0..self.dimension)
+            .map(|i| (0..self.dimension).map(|j| self.distance(i, j)).collect())
+            .collect()
+    }
+}
+
+impl Distances for TsplibInstance {
+    /// Distances as defined by TSPLIB, rounded to integers for the coordinate based types.
+    fn distance(&self, from: usize, to: usize) -> f64 {
+        if self.edge_weight_type == EdgeWeightType::Explicit {
+            return self.weights[from][to];
+        }
+
+        let (a, b) = (self.coordinates[from], self.coordinates[to]);
+        match self.edge_weight_type {
+            EdgeWeightType::Euc2d => nint(((a.0 - b.0).powi(2) + (a.1 - b.1).powi(2)).sqrt()),
+            EdgeWeightType::Att => {
+                let r = (((a.0 - b.0).powi(2) + (a.1 - b.1).powi(2)) / 10.0).sqrt();
+                let t = nint(r);
+                match t < r {
+                    true => t + 1.0,
+                    false => t,
+                }
+            }
+            EdgeWeightType::Geo => geo_distance(a, b),
+            EdgeWeightType::Explicit => unreachable!(),
+        }
+    }
+}
+
+/// Tour read from or written to a TSPLIB `.tour` file. Nodes are numbered from zero.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Tour {
+    pub name: String,
+    pub comment: Option<String>,
+    pub nodes: Vec<usize>,
+}
+
+impl Tour {
+    pub fn new(name: String, nodes: Vec<usize>) -> Self {
+        Self {
+            name,
+            comment: None,
+            nodes,
+        }
+    }
+
+    pub fn write<W: Write>(&self, writer: &mut W) -> Result<()> {
+        writeln!(writer, "NAME : {}", self.name)?;
+        if let Some(comment) = &self.comment {
+            for line in comment.lines() {
+                writeln!(writer, "COMMENT : {}", line)?;
+            }
+        }
+        writeln!(writer, "TYPE : TOUR")?;
+        writeln!(writer, "DIMENSION : {}", self.nodes.len())?;
+        writeln!(writer, "TOUR_SECTION")?;
+        for node in &self.nodes {
+            writeln!(writer, "{}", node + 1)?;
+        }
+        writeln!(writer, "-1")?;
+        writeln!(writer, "EOF")?;
+        Ok(())
+    }
+
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        self.write(&mut writer)?;
+        writer.flush()?;
+        Ok(())
+    }
+}
+
+pub fn read_tsp<P: AsRef<Path>>(path: P) -> Result<TsplibInstance> {
+    parse_tsp(&fs::read_to_string(path)?)
+}
+
+pub fn read_tour<P: AsRef<Path>>(path: P) -> Result<Tour> {
+    parse_tour(&fs::read_to_string(path)?)
+}
+
+pub fn parse_tsp(content: &str) -> Result<TsplibInstance> {
+    let file = TsplibFile::parse(content)?;
+    let dimension = file.dimension()?;
+
+    let edge_weight_type = match file.header("EDGE_WEIGHT_TYPE")? {
+        (_, "EUC_2D") => EdgeWeightType::Euc2d,
+        (_, "GEO") => EdgeWeightType::Geo,
+        (_, "ATT") => EdgeWeightType::Att,
+        (_, "EXPLICIT") => EdgeWeightType::Explicit,
+        (line, other) => {
+            return Err(parse_error(
+                line,
+                format!("unsupported EDGE_WEIGHT_TYPE {other}"),
+            ))
+        }
+    };
+
+    let (coordinates, weights) = match edge_weight_type {
+        EdgeWeightType::Explicit => (vec![], file.weights(dimension)?),
+        _ => (file.coordinates(dimension)?, vec![]),
+    };
+
+    Ok(TsplibInstance {
+        name: file.name(),
+        comment: file.comment(),
+        dimension,
+        edge_weight_type,
+        coordinates,
+        weights,
+    })
+}
+
+pub fn parse_tour(content: &str) -> Result<Tour> {
+    let file = TsplibFile::parse(content)?;
+    if let Ok((line, kind)) = file.header("TYPE") {
+        if kind != "TOUR" {
+            return Err(parse_error(
+                line,
+                format!("expected TYPE TOUR, found {kind}"),
+            ));
+        }
+    }
+
+    // Line where the tour ends, the terminating -1 or the last node
+    let (mut end, tokens) = file.section("TOUR_SECTION")?;
+    let mut nodes = vec![];
+    let mut lines = vec![];
+    for &(line, token) in tokens {
+        end = line;
+        let node: i64 = parse_number(line, token)?;
+        if node == -1 {
+            break;
+        }
+        if node < 1 {
+            return Err(parse_error(line, format!("invalid node {node}")));
+        }
+        nodes.push(node as usize - 1);
+        lines.push(line);
+    }
+
+    let dimension = match file.header("DIMENSION") {
+        Ok(_) => file.dimension()?,
+        Err(_) => nodes.len(),
+    };
+    let mut seen = vec![false; dimension];
+    for (node, &line) in nodes.iter().zip(&lines) {
+        match seen.get_mut(*node) {
+            Some(seen) if !*seen => *seen = true,
+            Some(_) => return Err(parse_error(line, format!("node {} repeats", node + 1))),
+            None => return Err(parse_error(line, format!("node {} out of range", node + 1))),
+        }
+    }
+    if nodes.len() != dimension {
+        let message = format!("tour has {} nodes, expected {dimension}", nodes.len());
+        return Err(parse_error(end, message));
+    }
+
+    Ok(Tour {
+        name: file.name(),
+        comment: file.comment(),
+        nodes,
+    })
+}
+
+fn parse_error(line: usize, message: String) -> Error {
+    Error::Parse { line, message }
+}
+
+fn parse_number<T: std::str::FromStr>(line: usize, token: &str) -> Result<T> {
+    token
+        .parse()
+        .map_err(|_| parse_error(line, format!("invalid number {token}")))
+}
+
+// TSPLIB rounds to the nearest integer with `(int) (x + 0.5)`
+fn nint(x: f64) -> f64 {
+    (x + 0.5).floor()
+}
+
+fn geo_distance(a: (f64, f64), b: (f64, f64)) -> f64 {
+    // TSPLIB uses this truncated value of pi, published optima depend on it
+    #[allow(clippy::approx_constant)]
+    const PI: f64 = 3.141592;
+    const RADIUS: f64 = 6378.388;
+
+    // Coordinates are given as DDD.MM (degrees and minutes)
+    let radians = |x: f64| {
+        let degrees = x.trunc();
+        PI * (degrees + 5.0 * (x - degrees) / 3.0) / 180.0
+    };
+    let (latitude_a, longitude_a) = (radians(a.0), radians(a.1));
+    let (latitude_b, longitude_b) = (radians(b.0), radians(b.1));
+
+    let q1 = (longitude_a - longitude_b).cos();
+    let q2 = (latitude_a - latitude_b).cos();
+    let q3 = (latitude_a + latitude_b).cos();
+    (RADIUS * (0.5 * ((1.0 + q1) * q2 - (1.0 - q1) * q3)).acos() + 1.0).trunc()
+}
+
+// Whitespace separated values with their line numbers
+type Tokens<'a> = Vec<(usize, &'a str)>;
+
+/// Specification entries and data sections of a TSPLIB file, with line numbers kept for errors.
+struct TsplibFile<'a> {
+    header: Vec<(usize, &'a str, &'a str)>,
+    sections: Vec<(usize, &'a str, Tokens<'a>)>,
+    last_line: usize,
+}
+
+impl<'a> TsplibFile<'a> {
+    fn parse(content: &'a str) -> Result<Self> {
+        let mut file = TsplibFile {
+            header: vec![],
+            sections: vec![],
+            last_line: content.lines().count(),
+        };
+
+        for (i, line) in content.lines().enumerate() {
+            let (number, line) = (i + 1, line.trim());
+            if line.is_empty() {
+                continue;
+            }
+            if line == "EOF" {
+                break;
+            }
+
+            if let Some((key, value)) = line.split_once(':') {
+                if !file.sections.is_empty() && key.trim().ends_with("_SECTION") {
+                    file.sections.push((number, key.trim(), vec![]));
+                } else if file.sections.is_empty() {
+                    file.header.push((number, key.trim(), value.trim()));
+                } else {
+                    return Err(parse_error(number, format!("unexpected entry {line}")));
+                }
+                continue;
+            }
+
+            let first = line.split_whitespace().next().unwrap_or_default();
+            if first.ends_with("_SECTION") {
+                file.sections.push((number, first, vec![]));
+                continue;
+            }
+
+            match file.sections.last_mut() {
+                Some((_, _, tokens)) => {
+                    tokens.extend(line.split_whitespace().map(|token| (number, token)))
+                }
+                None => return Err(parse_error(number, format!("unexpected line {line}"))),
+            }
+        }
+
+        Ok(file)
+    }
+
+    fn header(&self, key: &str) -> Result<(usize, &'a str)> {
+        self.header
+            .iter()
+            .find(|(_, k, _)| *k == key)
+            .map(|(line, _, value)| (*line, *value))
+            .ok_or_else(|| parse_error(self.last_line, format!("missing {key}")))
+    }
+
+    fn section(&self, name: &str) -> Result<(usize, &[(usize, &'a str)])> {
+        self.sections
+            .iter()
+            .find(|(_, n, _)| *n == name)
+            .map(|(line, _, tokens)| (*line, tokens.as_slice()))
+            .ok_or_else(|| parse_error(self.last_line, format!("missing {name}")))
+    }
+
+    fn name(&self) -> String {
+        self.header("NAME")
+            .map(|(_, name)| name.to_string())
+            .unwrap_or_default()
+    }
+
+    fn comment(&self) -> Option<String> {
+        let comments: Vec<&str> = self
+            .header
+            .iter()
+            .filter(|(_, key, _)| *key == "COMMENT")
+            .map(|(_, _, value)| *value)
+            .collect();
+        match comments.is_empty() {
+            true => None,
+            false => Some(comments.join("\n")),
+        }
+    }
+
+    fn dimension(&self) -> Result<usize> {
+        let (line, value) = self.header("DIMENSION")?;
+        parse_number(line, value)
+    }
+
+    fn coordinates(&self, dimension: usize) -> Result<Vec<(f64, f64)>> {
+        let (line, tokens) = self.section("NODE_COORD_SECTION")?;
+        if tokens.len() != 3 * dimension {
+            let message = format!("expected {dimension} nodes with two coordinates each");
+            return Err(parse_error(line, message));
+        }
+
+        let mut coordinates = vec![None; dimension];
+        for node in tokens.chunks(3) {
+            let (line, id) = node[0];
+            let id: usize = parse_number(line, id)?;
+            let x = parse_number(node[1].0, node[1].1)?;
+            let y = parse_number(node[2].0, node[2].1)?;
+
+            match coordinates.get_mut(id.wrapping_sub(1)) {
+                Some(slot @ None) => *slot = Some((x, y)),
+                Some(_) => return Err(parse_error(line, format!("node {id} repeats"))),
+                None => return Err(parse_error(line, format!("node {id} out of range"))),
+            }
+        }
+
+        Ok(coordinates.into_iter().flatten().collect())
+    }
+
+    fn weights(&self, dimension: usize) -> Result<Vec<Vec<f64>>> {
+        let (format_line, format) = self.header("EDGE_WEIGHT_FORMAT")?;
+        let (line, tokens) = self.section("EDGE_WEIGHT_SECTION")?;
+        let values = tokens
+            .iter()
+            .map(|(line, token)| parse_number(*line, token))
+            .collect::<Result<Vec<f64>>>()?;
+
+        let expected = match format {
+            "FULL_MATRIX" => dimension * dimension,
+            "UPPER_ROW" => dimension * dimension.saturating_sub(1) / 2,
+            other => {
+                let message = format!("unsupported EDGE_WEIGHT_FORMAT {other}");
+                return Err(parse_error(format_line, message));
+            }
+        };
+        if values.len() != expected {
+            let message = format!("expected {expected} weights, found {}", values.len());
+            return Err(parse_error(line, message));
+        }
+
+        if format == "FULL_MATRIX" {
+            return Ok(values
+                .chunks(dimension.max(1))
+                .map(<[f64]>::to_vec)
+                .collect());
+        }
+
+        let mut weights = vec![vec![0.0; dimension]; dimension];
+        let mut values = values.into_iter();
+        for (i, j) in (0..dimension).flat_map(|i| (i + 1..dimension).map(move |j| (i, j))) {
+            let value = values.next().unwrap_or_default();
+            weights[i][j] = value;
+            weights[j][i] = value;
+        }
+        Ok(weights)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_tour, parse_tsp, EdgeWeightType, Tour};
+    use crate::{
+        permutation::{tour_length, Distances},
+        Error,
+    };
+
+    const BURMA: &str = "NAME: burma3
+TYPE: TSP
+COMMENT: First three cities of burma14
+DIMENSION: 3
+EDGE_WEIGHT_TYPE: GEO
+NODE_COORD_SECTION
+   1  16.47       96.10
+   2  16.47       94.44
+   3  20.09       92.54
+EOF
+";
+
+    #[test]
+    fn parses_coordinate_instances() {
+        let instance = parse_tsp(BURMA).unwrap();
+        assert_eq!("burma3", instance.name);
+        assert_eq!(EdgeWeightType::Geo, instance.edge_weight_type);
+        // Published burma14 distances
+        assert_eq!(153.0, instance.distance(0, 1));
+        assert_eq!(510.0, instance.distance(0, 2));
+        assert_eq!(422.0, instance.distance(1, 2));
+
+        let euclidean = BURMA.replace("GEO", "EUC_2D");
+        let instance = parse_tsp(&euclidean).unwrap();
+        assert_eq!(2.0, instance.distance(0, 1));
+
+        let att = BURMA.replace("GEO", "ATT");
+        let instance = parse_tsp(&att).unwrap();
+        assert_eq!(1.0, instance.distance(0, 1));
+    }
+
+    #[test]
+    fn parses_explicit_instances() {
+        let full = "NAME : full
+TYPE : TSP
+DIMENSION : 3
+EDGE_WEIGHT_TYPE : EXPLICIT
+EDGE_WEIGHT_FORMAT : FULL_MATRIX
+EDGE_WEIGHT_SECTION
+0 1 2
+1 0 3
+2 3 0
+EOF";
+        let upper = "NAME : upper
+DIMENSION : 3
+EDGE_WEIGHT_TYPE : EXPLICIT
+EDGE_WEIGHT_FORMAT : UPPER_ROW
+EDGE_WEIGHT_SECTION
+1 2
+3
+";
+        let full = parse_tsp(full).unwrap();
+        let upper = parse_tsp(upper).unwrap();
+        assert_eq!(full.distance_matrix(), upper.distance_matrix());
+        assert_eq!(6.0, tour_length(&[0, 1, 2], &full));
+    }
+
+    #[test]
+    fn tours_round_trip() {
+        let mut tour = Tour::new("burma3".to_string(), vec![2, 0, 1]);
+        tour.comment = Some("Length 1039".to_string());
+
+        let mut written = vec![];
+        tour.write(&mut written).unwrap();
+        let read = parse_tour(&String::from_utf8(written).unwrap()).unwrap();
+
+        assert_eq!(tour, read);
+    }
+
+    #[test]
+    fn malformed_input_reports_line() {
+        let error = parse_tsp(&BURMA.replace("20.09", "20,09")).unwrap_err();
+        assert!(matches!(error, Error::Parse { line: 9, .. }));
+
+        let error = parse_tsp(&BURMA.replace("   3  20", "   2  20")).unwrap_err();
+        assert!(matches!(error, Error::Parse { line: 9, .. }));
+
+        let error = parse_tsp(&BURMA.replace("DIMENSION: 3", "DIMENSION: 4")).unwrap_err();
+        assert!(matches!(error, Error::Parse { line: 6, .. }));
+
+        let error = parse_tour("TYPE : TOUR\nTOUR_SECTION\n1\n1\n-1\n").unwrap_err();
+        assert!(matches!(error, Error::Parse { line: 4, .. }));
+
+        let tour = "DIMENSION : 2\nTOUR_SECTION\n1\n3\n-1\n";
+        let error = parse_tour(tour).unwrap_err();
+        assert!(matches!(error, Error::Parse { line: 4, .. }));
+
+        let tour = "DIMENSION : 3\nTOUR_SECTION\n1\n2\n-1\n";
+        let error = parse_tour(tour).unwrap_err();
+        assert!(matches!(error, Error::Parse { line: 5, .. }));
+    }
+}
@@ -1,5 +1,17 @@
+// Lets the derive macros refer to `::optima_rust` from inside this crate too
+extern crate self as optima_rust;
+
 pub mod analysis;
 pub mod annealing;
 pub mod base;
+pub mod benchmarks;
 pub mod genetic;
+pub mod io;
+pub mod permutation;
+#[cfg(feature = "problems")]
+pub mod problems;
 pub mod swarm;
+pub mod tabu;
+
+pub use self::error::{Error, Result};
+mod error;
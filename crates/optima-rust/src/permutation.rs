@@ -0,0 +1,270 @@
+use rand::Rng;
+
+/// Cost of travelling between two nodes of a tour.
+pub trait Distances {
+    fn distance(&self, from: usize, to: usize) -> f64;
+}
+
+impl Distances for Vec<Vec<f64>> {
+    fn distance(&self, from: usize, to: usize) -> f64 {
+        self[from][to]
+    }
+}
+
+/// Length of the closed tour, including the edge from the last node back to the first one.
+pub fn tour_length<D: Distances + ?Sized>(tour: &[usize], distances: &D) -> f64 {
+    let n = tour.len();
+    (0..n)
+        .map(|i| distances.distance(tour[i], tour[(i + 1) % n]))
+        .sum()
+}
+
+/// Change of a permutation. Every move can be applied to any slice, `tour_delta` gives the change
+/// of `tour_length` it causes without recomputing the whole tour.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Move {
+    /// Exchanges the elements at two positions.
+    Swap(usize, usize),
+    /// Reverses the inclusive range `first..=last`.
+    Reverse(usize, usize),
+    /// Takes `len` elements starting at `from` and reinserts them so they start at `to`.
+    Shift { from: usize, len: usize, to: usize },
+}
+
+impl Move {
+    /// Moves the element at `from` to position `to`.
+    pub fn insertion(from: usize, to: usize) -> Self {
+        Move::Shift { from, len: 1, to }
+    }
+
+    /// Replaces the edges after positions `i` and `j` (`i < j`) by reversing the path between them.
+    pub fn two_opt(i: usize, j: usize) -> Self {
+        Move::Reverse(i + 1, j)
+    }
+
+    /// Moves a segment of `len` (usually up to three) elements.
+    pub fn or_opt(from: usize, len: usize, to: usize) -> Self {
+        Move::Shift { from, len, to }
+    }
+
+    pub fn apply<T>(&self, tour: &mut [T]) {
+        match *self {
+            Move::Swap(a, b) => tour.swap(a, b),
+            Move::Reverse(first, last) => tour[first..=last].reverse(),
+            Move::Shift { from, len, to } if to > from => tour[from..to + len].rotate_left(len),
+            Move::Shift { from, len, to } => tour[to..from + len].rotate_right(len),
+        }
+    }
+
+    /// Change of `tour_length` after applying the move, computed in O(1).
+    /// Assumes symmetric distances, reversed paths are expected to cost the same.
+    pub fn tour_delta<D: Distances + ?Sized>(&self, tour: &[usize], distances: &D) -> f64 {
+        let n = tour.len();
+        if n < 2 {
+            return 0.0;
+        }
+
+        // Indexes of the edges (k, k + 1) that differ before and after the move
+        let before = |k: usize| (k + n - 1) % n;
+        let (removed, added) = match *self {
+            Move::Swap(a, b) => {
+                let edges = vec![before(a), a, before(b), b];
+                (edges.clone(), edges)
+            }
+            Move::Reverse(first, last) => {
+                let edges = vec![before(first), last];
+                (edges.clone(), edges)
+            }
+            Move::Shift { from, len, to } if to > from => (
+                vec![before(from), from + len - 1, to + len - 1],
+                vec![before(from), before(to), to + len - 1],
+            ),
+            Move::Shift { from, len, to } => (
+                vec![before(to), before(from), from + len - 1],
+                vec![before(to), to + len - 1, from + len - 1],
+            ),
+        };
+
+        let moved = |k: usize| tour[self.source(k)];
+        edges_cost(added, moved, n, distances) - edges_cost(removed, |k| tour[k], n, distances)
+    }
+
+    // Position in the original tour of the element found at `k` after the move
+    fn source(&self, k: usize) -> usize {
+        match *self {
+            Move::Swap(a, b) if k == a => b,
+            Move::Swap(a, b) if k == b => a,
+            Move::Reverse(first, last) if (first..=last).contains(&k) => first + last - k,
+            Move::Shift { from, len, to } if to > from && (from..to).contains(&k) => k + len,
+            Move::Shift { from, len, to } if to > from && (to..to + len).contains(&k) => {
+                k - to + from
+            }
+            Move::Shift { from, len, to } if to < from && (to..to + len).contains(&k) => {
+                k - to + from
+            }
+            Move::Shift { from, len, to } if to < from && (to + len..from + len).contains(&k) => {
+                k - len
+            }
+            _ => k,
+        }
+    }
+}
+
+fn edges_cost<D, F>(mut edges: Vec<usize>, at: F, n: usize, distances: &D) -> f64
+where
+    D: Distances + ?Sized,
+    F: Fn(usize) -> usize,
+{
+    edges.sort_unstable();
+    edges.dedup();
+    edges
+        .into_iter()
+        .map(|k| distances.distance(at(k), at((k + 1) % n)))
+        .sum()
+}
+
+/// Kind of move, used to draw random moves (e.g. in a `ChangeFn`) or to list all of them
+/// (e.g. in a `NeighbourhoodFn`).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Neighbourhood {
+    Swap,
+    Insertion,
+    Inversion,
+    TwoOpt,
+    /// Segments of one to three elements.
+    OrOpt,
+}
+
+impl Neighbourhood {
+    /// Random move for a tour of `len` elements, tours shorter than two elements get a no-op.
+    pub fn sample<R: Rng + ?Sized>(&self, len: usize, rng: &mut R) -> Move {
+        if len < 2 {
+            return Move::Swap(0, 0);
+        }
+
+        let a = rng.gen_range(0..len);
+        let b = (a + rng.gen_range(1..len)) % len;
+        match self {
+            Neighbourhood::Swap => Move::Swap(a, b),
+            Neighbourhood::Insertion => Move::insertion(a, b),
+            Neighbourhood::Inversion => Move::Reverse(a.min(b), a.max(b)),
+            Neighbourhood::TwoOpt => Move::two_opt(a.min(b), a.max(b)),
+            Neighbourhood::OrOpt => {
+                let segment = rng.gen_range(1..=3.min(len - 1));
+                let from = rng.gen_range(0..=len - segment);
+                let to = rng.gen_range(0..=len - segment);
+                Move::or_opt(from, segment, to)
+            }
+        }
+    }
+
+    /// Every distinct move of this kind for a tour of `len` elements.
+    pub fn moves(&self, len: usize) -> Vec<Move> {
+        let pairs = (0..len).flat_map(|a| (a + 1..len).map(move |b| (a, b)));
+        match self {
+            Neighbourhood::Swap => pairs.map(|(a, b)| Move::Swap(a, b)).collect(),
+            Neighbourhood::Inversion => pairs.map(|(a, b)| Move::Reverse(a, b)).collect(),
+            Neighbourhood::TwoOpt => pairs
+                .filter(|&(a, b)| b > a + 1 && !(a == 0 && b == len - 1))
+                .map(|(a, b)| Move::two_opt(a, b))
+                .collect(),
+            Neighbourhood::Insertion => (0..len)
+                .flat_map(|from| (0..len).map(move |to| (from, to)))
+                .filter(|(from, to)| from != to)
+                .map(|(from, to)| Move::insertion(from, to))
+                .collect(),
+            Neighbourhood::OrOpt => (1..=3.min(len))
+                .flat_map(|segment| {
+                    (0..=len - segment).flat_map(move |from| {
+                        (0..=len - segment)
+                            .filter(move |to| *to != from)
+                            .map(move |to| Move::or_opt(from, segment, to))
+                    })
+                })
+                .collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    use super::{tour_length, Move, Neighbourhood};
+
+    const ALL: [Neighbourhood; 5] = [
+        Neighbourhood::Swap,
+        Neighbourhood::Insertion,
+        Neighbourhood::Inversion,
+        Neighbourhood::TwoOpt,
+        Neighbourhood::OrOpt,
+    ];
+
+    fn random_distances(n: usize, rng: &mut StdRng) -> Vec<Vec<f64>> {
+        let mut distances = vec![vec![0.0; n]; n];
+        for (i, j) in (0..n).flat_map(|i| (i + 1..n).map(move |j| (i, j))) {
+            distances[i][j] = rng.gen_range(1.0..10.0);
+            distances[j][i] = distances[i][j];
+        }
+        distances
+    }
+
+    #[test]
+    fn moves_keep_permutations() {
+        let mut tour = vec![0, 1, 2, 3, 4, 5];
+        Move::insertion(1, 4).apply(&mut tour);
+        assert_eq!(vec![0, 2, 3, 4, 1, 5], tour);
+        Move::or_opt(3, 2, 0).apply(&mut tour);
+        assert_eq!(vec![4, 1, 0, 2, 3, 5], tour);
+        Move::two_opt(0, 3).apply(&mut tour);
+        assert_eq!(vec![4, 2, 0, 1, 3, 5], tour);
+    }
+
+    #[test]
+    fn tour_length_includes_closing_edge() {
+        let distances = vec![
+            vec![0.0, 1.0, 5.0],
+            vec![1.0, 0.0, 2.0],
+            vec![5.0, 2.0, 0.0],
+        ];
+        assert_eq!(8.0, tour_length(&[0, 1, 2], &distances));
+    }
+
+    #[test]
+    fn delta_matches_full_evaluation() {
+        let mut rng = StdRng::seed_from_u64(13);
+
+        for n in 2..8 {
+            let distances = random_distances(n, &mut rng);
+            let mut tour: Vec<usize> = (0..n).collect();
+            Neighbourhood::Swap.sample(n, &mut rng).apply(&mut tour);
+
+            for neighbourhood in ALL {
+                for m in neighbourhood.moves(n) {
+                    let mut moved = tour.clone();
+                    m.apply(&mut moved);
+
+                    let expected = tour_length(&moved, &distances) - tour_length(&tour, &distances);
+                    let delta = m.tour_delta(&tour, &distances);
+                    assert!((expected - delta).abs() < 1e-9, "{:?} on {:?}", m, tour);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn sampled_moves_are_in_bounds() {
+        let mut rng = StdRng::seed_from_u64(13);
+        let mut tour: Vec<usize> = (0..10).collect();
+
+        for _ in 0..100 {
+            for neighbourhood in ALL {
+                neighbourhood.sample(tour.len(), &mut rng).apply(&mut tour);
+            }
+        }
+
+        let mut sorted = tour.clone();
+        sorted.sort();
+        assert_eq!((0..10).collect::<Vec<_>>(), sorted);
+    }
+}
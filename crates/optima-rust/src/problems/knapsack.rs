@@ -0,0 +1,515 @@
+use rand::{prelude::ThreadRng, thread_rng, Rng};
+
+use crate::{
+    analysis::AsCsvRow,
+    base::{
+        solution_attr, Chromosome, Delta, DeltaMove, DerivedSolution, Evaluation, Problem, Solution,
+    },
+    Error, Result,
+};
+
+/// 0-1 knapsack problem, the number of items is only known at runtime.
+#[derive(Clone, Debug)]
+pub struct KnapsackProblem {
+    pub name: String,
+    pub weights: Vec<f64>,
+    pub values: Vec<f64>,
+    pub capacity: f64,
+    /// Position of the instance within a generated batch
+    pub instance: u32,
+}
+
+impl KnapsackProblem {
+    pub fn new(name: String, weights: Vec<f64>, values: Vec<f64>, capacity: f64) -> Self {
+        Self {
+            name,
+            weights,
+            values,
+            capacity,
+            instance: 0,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.weights.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.weights.is_empty()
+    }
+}
+
+impl Problem for KnapsackProblem {}
+
+#[solution_attr]
+#[derive(Clone, Debug, DerivedSolution)]
+pub struct KnapsackSolution {
+    /// Changed only through `genes_mut` or `flip`, which keep `tracked_weight` up to date.
+    picked_items: Vec<bool>,
+    /// Weight of the picked items when known, lets `Flip` compute its delta in O(1).
+    tracked_weight: Option<f64>,
+}
+
+impl KnapsackSolution {
+    pub fn new(picked_items: Vec<bool>) -> Self {
+        Self {
+            picked_items,
+            tracked_weight: None,
+            eval: Evaluation::default(),
+        }
+    }
+
+    /// Nothing picked, always feasible.
+    pub fn empty(problem: &KnapsackProblem) -> Self {
+        let mut solution = Self::new(vec![false; problem.len()]);
+        solution.tracked_weight = Some(0.0);
+        solution
+    }
+
+    /// Every item is picked with probability 0.5.
+    pub fn random<R: Rng + ?Sized>(problem: &KnapsackProblem, rng: &mut R) -> Self {
+        let mut solution = Self::new((0..problem.len()).map(|_| rng.gen()).collect());
+        solution.tracked_weight = Some(solution.weight(problem));
+        solution
+    }
+
+    pub fn picked_items(&self) -> &[bool] {
+        &self.picked_items
+    }
+
+    /// Sums the weights of the picked items.
+    pub fn weight(&self, problem: &KnapsackProblem) -> f64 {
+        picked_sum(&self.picked_items, &problem.weights)
+    }
+}
+
+impl Chromosome for KnapsackSolution {
+    type Gene = bool;
+
+    fn genes(&self) -> &[bool] {
+        &self.picked_items
+    }
+
+    fn genes_mut(&mut self) -> &mut [bool] {
+        self.tracked_weight = None;
+        &mut self.picked_items
+    }
+}
+
+impl AsCsvRow for KnapsackSolution {
+    fn as_row(&self, i: usize) -> String {
+        format!("{},{}", i, self.get_value())
+    }
+}
+
+fn picked_sum(picked_items: &[bool], of: &[f64]) -> f64 {
+    picked_items
+        .iter()
+        .zip(of)
+        .filter(|(picked, _)| **picked)
+        .map(|(_, x)| x)
+        .sum()
+}
+
+/// Total value of the picked items, to be maximized.
+pub fn value(problem: &KnapsackProblem, solution: &KnapsackSolution) -> f64 {
+    picked_sum(&solution.picked_items, &problem.values)
+}
+
+/// Negative overweight, zero when the items fit.
+pub fn penalty(problem: &KnapsackProblem, solution: &KnapsackSolution) -> f64 {
+    f64::min(problem.capacity - solution.weight(problem), 0.0)
+}
+
+/// Flips whether the item at the index is picked.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Flip(pub usize);
+
+/// `None` when the weight of `before` isn't tracked.
+impl DeltaMove<KnapsackProblem, KnapsackSolution> for Flip {
+    fn delta(&self, problem: &KnapsackProblem, before: &KnapsackSolution) -> Option<Delta> {
+        let sign = flip_sign(before.picked_items[self.0]);
+        let weight = before.tracked_weight?;
+        let penalty = |weight: f64| f64::min(problem.capacity - weight, 0.0);
+
+        Some(Delta {
+            penalty: penalty(weight + sign * problem.weights[self.0]) - penalty(weight),
+            value: sign * problem.values[self.0],
+        })
+    }
+}
+
+fn flip_sign(picked: bool) -> f64 {
+    match picked {
+        true => -1.0,
+        false => 1.0,
+    }
+}
+
+/// Change function flipping a random item, starts tracking the weight of the solution.
+pub fn flip<R: Rng + ?Sized>(
+    solution: &mut KnapsackSolution,
+    problem: &KnapsackProblem,
+    rng: &mut R,
+) -> Flip {
+    let index = rng.gen_range(0..solution.picked_items.len());
+    let weight = match solution.tracked_weight {
+        Some(weight) => weight,
+        None => solution.weight(problem),
+    };
+
+    let sign = flip_sign(solution.picked_items[index]);
+    solution.tracked_weight = Some(weight + sign * problem.weights[index]);
+    solution.picked_items[index] = !solution.picked_items[index];
+    Flip(index)
+}
+
+/// Correlation between weights and values of generated items, after Pisinger's
+/// "Where are the hard knapsack problems?". `R` below is the data range.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Generator {
+    /// Weights and values from `[1, R]`
+    Uncorrelated,
+    /// Values within `R / 10` of the weights
+    WeaklyCorrelated,
+    /// Values are weights plus `R / 10`
+    StronglyCorrelated,
+    /// Weights are values plus `R / 10`
+    InverseStrong,
+    /// Weights from `[100000, 100100]`, values from `[1, 1000]`
+    SimilarWeight,
+    /// Values equal to weights
+    SubsetSum,
+    /// Values within `R / 500` of weights plus `R / 10`
+    AlmostStrong,
+}
+
+impl Generator {
+    /// Generates `n` items with data range `range`.
+    pub fn generate<R: Rng + ?Sized>(
+        &self,
+        name: String,
+        n: usize,
+        range: f64,
+        capacity: f64,
+        rng: &mut R,
+    ) -> KnapsackProblem {
+        let mut weights = Vec::with_capacity(n);
+        let mut values = Vec::with_capacity(n);
+
+        for _ in 0..n {
+            let x = rng.gen_range(1.0..=range);
+            let (weight, value) = match self {
+                Generator::Uncorrelated => (x, rng.gen_range(1.0..=range)),
+                Generator::WeaklyCorrelated => {
+                    let spread = range / 10.0;
+                    (x, rng.gen_range(f64::max(x - spread, 1.0)..=x + spread))
+                }
+                Generator::StronglyCorrelated => (x, x + range / 10.0),
+                Generator::InverseStrong => (x + range / 10.0, x),
+                Generator::SimilarWeight => (
+                    rng.gen_range(100000.0..=100100.0),
+                    rng.gen_range(1.0..=1000.0),
+                ),
+                Generator::SubsetSum => (x, x),
+                Generator::AlmostStrong => {
+                    let center = x + range / 10.0;
+                    (
+                        x,
+                        rng.gen_range(center - range / 500.0..=center + range / 500.0),
+                    )
+                }
+            };
+            weights.push(weight);
+            values.push(value);
+        }
+
+        KnapsackProblem::new(name, weights, values, capacity)
+    }
+}
+
+/// Generates batches of `how_many_problems` random instances with `n` items and capacity `b`.
+pub struct KnapsackInstanceFactory<R: Rng = ThreadRng> {
+    n: usize,
+    b: f64,
+    how_many_problems: u32,
+    problems: Vec<KnapsackProblem>,
+    rng: R,
+}
+
+impl KnapsackInstanceFactory<ThreadRng> {
+    pub fn new(n: usize, b: f64, how_many_problems: u32) -> Self {
+        Self::with_rng(n, b, how_many_problems, thread_rng())
+    }
+}
+
+impl<R: Rng> KnapsackInstanceFactory<R> {
+    pub fn with_rng(n: usize, b: f64, how_many_problems: u32, rng: R) -> Self {
+        Self {
+            n,
+            b,
+            how_many_problems,
+            rng,
+            problems: Vec::with_capacity(how_many_problems as usize),
+        }
+    }
+
+    fn generate(&mut self, generator: Generator, coefficient: f64) -> KnapsackProblem {
+        let range = f64::powf(10.0, coefficient);
+        let name = format!("{:?}", generator);
+        generator.generate(name, self.n, range, self.b, &mut self.rng)
+    }
+
+    /// Instances drawn directly from `generator` with data range `10^coefficient`.
+    pub fn generate_distribution_problem(
+        &mut self,
+        generator: Generator,
+        coefficient: f64,
+    ) -> &mut Self {
+        for _ in 0..self.how_many_problems {
+            let problem = self.generate(generator, coefficient);
+            self.problems.push(problem);
+        }
+        self
+    }
+
+    /// Every item is a multiple (from `[1, m]`) of one of `v` spanner items drawn from `generator`.
+    pub fn generate_spanner_problems(
+        &mut self,
+        generator: Generator,
+        coefficient: f64,
+        v: usize,
+        m: f64,
+    ) -> &mut Self {
+        for _ in 0..self.how_many_problems {
+            let mut problem = self.generate(generator, coefficient);
+            let v = match self.n {
+                0 => 0,
+                n => v.clamp(1, n),
+            };
+            let spanner_weights: Vec<f64> = (0..v).map(|k| problem.weights[k] * 2.0 / m).collect();
+            let spanner_values: Vec<f64> = (0..v).map(|k| problem.values[k] * 2.0 / m).collect();
+
+            for i in 0..self.n {
+                let s = self.rng.gen_range(0..v);
+                let a = self.rng.gen_range(1.0..=m);
+                problem.weights[i] = spanner_weights[s] * a;
+                problem.values[i] = spanner_values[s] * a;
+            }
+            self.problems.push(problem);
+        }
+        self
+    }
+
+    /// Multiple strongly correlated: integer weights divisible by `d` get value weight + `k1`,
+    /// the others weight + `k2`. Fails when `k1` equals `k2`.
+    pub fn generate_mstr_problems(
+        &mut self,
+        generator: Generator,
+        coefficient: f64,
+        k1: f64,
+        k2: f64,
+        d: f64,
+    ) -> Result<&mut Self> {
+        if k1 == k2 {
+            return Err(Error::InvalidArgument(format!("k1 and k2 are both {k1}")));
+        }
+        let range = f64::powf(10.0, coefficient);
+
+        for _ in 0..self.how_many_problems {
+            let mut problem = self.generate(generator, coefficient);
+
+            for i in 0..self.n {
+                problem.weights[i] = self.rng.gen_range(1.0..=range).round();
+                problem.values[i] = match problem.weights[i] % d == 0.0 {
+                    true => problem.weights[i] + k1,
+                    false => problem.weights[i] + k2,
+                };
+            }
+
+            self.problems.push(problem);
+        }
+        Ok(self)
+    }
+
+    /// Profit ceiling: values are the weights rounded up to a multiple of `d`.
+    pub fn generate_pceil_problems(
+        &mut self,
+        generator: Generator,
+        coefficient: f64,
+        d: f64,
+    ) -> &mut Self {
+        let range = f64::powf(10.0, coefficient);
+
+        for _ in 0..self.how_many_problems {
+            let mut problem = self.generate(generator, coefficient);
+
+            for i in 0..self.n {
+                problem.weights[i] = self.rng.gen_range(1.0..=range);
+                problem.values[i] = d * (problem.weights[i] / d).ceil();
+            }
+
+            self.problems.push(problem);
+        }
+        self
+    }
+
+    /// Values lie on a circle: `d * sqrt(4R^2 - (w - 2R)^2)`.
+    pub fn generate_circle_problems(
+        &mut self,
+        generator: Generator,
+        coefficient: f64,
+        d: f64,
+    ) -> &mut Self {
+        let range = f64::powf(10.0, coefficient);
+
+        for _ in 0..self.how_many_problems {
+            let mut problem = self.generate(generator, coefficient);
+
+            for i in 0..self.n {
+                problem.weights[i] = self.rng.gen_range(1.0..=range);
+                problem.values[i] =
+                    d * f64::sqrt(4.0 * range.powi(2) - (problem.weights[i] - 2.0 * range).powi(2));
+            }
+
+            self.problems.push(problem);
+        }
+        self
+    }
+
+    /// Takes all instances generated so far.
+    pub fn collect(&mut self) -> Vec<KnapsackProblem> {
+        std::mem::take(&mut self.problems)
+    }
+
+    pub fn change_parameters(&mut self, n: usize, b: f64, how_many_problems: u32) -> &mut Self {
+        self.n = n;
+        self.b = b;
+        self.how_many_problems = how_many_problems;
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use super::{
+        flip, penalty, value, Generator, KnapsackInstanceFactory, KnapsackProblem, KnapsackSolution,
+    };
+    use crate::{
+        base::{Chromosome, Criterion, EvaluationCounter, Solution},
+        Error,
+    };
+
+    #[test]
+    fn evaluates_value_and_overweight() {
+        let problem = KnapsackProblem::new(
+            "small".to_string(),
+            vec![1.0, 2.0, 3.0],
+            vec![4.0, 5.0, 6.0],
+            4.0,
+        );
+        let criterion = Criterion::new(&penalty, &value, false);
+
+        let mut fits = KnapsackSolution::new(vec![true, false, true]);
+        criterion.evaluate(&problem, &mut fits);
+        assert_eq!(10.0, fits.get_value());
+        assert!(fits.get_eval().is_feasible);
+
+        let mut heavy = KnapsackSolution::new(vec![true, true, true]);
+        criterion.evaluate(&problem, &mut heavy);
+        assert_eq!(2.0, heavy.get_value());
+        assert_eq!(2.0, heavy.get_eval().violation);
+        assert!(!heavy.get_eval().is_feasible);
+    }
+
+    #[test]
+    fn flip_deltas_match_full_evaluation() {
+        let mut rng = StdRng::seed_from_u64(16);
+        let problem = Generator::Uncorrelated.generate("u".to_string(), 30, 100.0, 500.0, &mut rng);
+        let mut criterion = Criterion::new(&penalty, &value, false);
+        criterion.check_delta = true;
+
+        let counter = EvaluationCounter::new();
+        criterion.register_counter(&counter);
+
+        let mut solution = KnapsackSolution::new(vec![false; problem.len()]);
+        criterion.evaluate(&problem, &mut solution);
+        let mut incremental = 0;
+        for i in 0..200 {
+            let before = solution.clone();
+            let change = flip(&mut solution, &problem, &mut rng);
+            criterion.evaluate_move(&problem, &before, &mut solution, &change);
+            assert!((solution.weight(&problem) - solution.tracked_weight.unwrap()).abs() < 1e-9);
+
+            // The first flip doesn't know the weight, infeasible solutions have no delta
+            if i > 0 && before.get_eval().is_feasible {
+                incremental += 1;
+            }
+        }
+        assert!(incremental > 0);
+        assert_eq!(incremental, counter.incremental());
+
+        assert!(!solution.genes_mut().is_empty());
+        assert_eq!(None, solution.tracked_weight);
+    }
+
+    #[test]
+    fn factory_generates_any_length() {
+        let mut factory =
+            KnapsackInstanceFactory::with_rng(40, 250.0, 2, StdRng::seed_from_u64(16));
+        let generators = [
+            Generator::Uncorrelated,
+            Generator::WeaklyCorrelated,
+            Generator::StronglyCorrelated,
+            Generator::InverseStrong,
+            Generator::SimilarWeight,
+            Generator::SubsetSum,
+            Generator::AlmostStrong,
+        ];
+        for generator in generators {
+            factory.generate_distribution_problem(generator, 3.0);
+        }
+        factory
+            .generate_spanner_problems(Generator::Uncorrelated, 3.0, 2, 10.0)
+            .generate_mstr_problems(Generator::Uncorrelated, 3.0, 300.0, 200.0, 6.0)
+            .unwrap()
+            .generate_pceil_problems(Generator::Uncorrelated, 3.0, 3.0)
+            .generate_circle_problems(Generator::Uncorrelated, 3.0, 2.0 / 3.0);
+
+        let problems = factory.collect();
+        assert_eq!(22, problems.len());
+        for problem in &problems {
+            assert_eq!(40, problem.len());
+            assert_eq!(40, problem.values.len());
+            assert!(problem
+                .weights
+                .iter()
+                .chain(&problem.values)
+                .all(|x| *x > 0.0));
+        }
+        assert_eq!("StronglyCorrelated", problems[4].name);
+
+        let problems = factory
+            .change_parameters(7, 10.0, 1)
+            .generate_distribution_problem(Generator::SubsetSum, 2.0)
+            .collect();
+        assert_eq!(7, problems[0].len());
+        assert_eq!(problems[0].weights, problems[0].values);
+    }
+
+    #[test]
+    fn factory_rejects_bad_parameters_without_panicking() {
+        let mut factory = KnapsackInstanceFactory::with_rng(0, 10.0, 1, StdRng::seed_from_u64(16));
+
+        let problems = factory
+            .generate_spanner_problems(Generator::Uncorrelated, 3.0, 2, 10.0)
+            .collect();
+        assert!(problems[0].is_empty());
+
+        let result = factory.generate_mstr_problems(Generator::Uncorrelated, 3.0, 5.0, 5.0, 6.0);
+        assert!(matches!(result, Err(Error::InvalidArgument(_))));
+        assert!(factory.collect().is_empty());
+    }
+}
@@ -0,0 +1,4 @@
+//! Ready to use benchmark problems with their solutions, evaluation functions and moves.
+
+pub mod knapsack;
+pub mod tsp;
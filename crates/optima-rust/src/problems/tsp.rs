@@ -0,0 +1,175 @@
+use std::path::Path;
+
+use rand::{seq::SliceRandom, Rng};
+
+use crate::{
+    analysis::AsCsvRow,
+    base::{
+        solution_attr, Chromosome, Delta, DeltaMove, DerivedSolution, Evaluation, Problem, Solution,
+    },
+    io::tsplib,
+    permutation::{tour_length, Distances, Move, Neighbourhood},
+    Result,
+};
+
+/// Symmetric travelling salesman problem over a full distance matrix.
+#[derive(Clone, Debug)]
+pub struct TspProblem {
+    pub name: String,
+    pub distances: Vec<Vec<f64>>,
+    pub best_known: Option<f64>,
+}
+
+impl TspProblem {
+    pub fn new(name: String, distances: Vec<Vec<f64>>) -> Self {
+        Self {
+            name,
+            distances,
+            best_known: None,
+        }
+    }
+
+    /// `n` nodes with uniformly random distances from `[0, 10)`.
+    pub fn random<R: Rng + ?Sized>(n: usize, rng: &mut R) -> Self {
+        let mut distances = vec![vec![0.0; n]; n];
+        for (i, j) in (0..n).flat_map(|i| (i + 1..n).map(move |j| (i, j))) {
+            distances[i][j] = 10.0 * rng.gen::<f64>();
+            distances[j][i] = distances[i][j];
+        }
+
+        Self::new(format!("random{n}"), distances)
+    }
+
+    /// Loads a TSPLIB instance, the best known length is taken from `<name>.opt.tour` next to it.
+    pub fn from_tsplib<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let instance = tsplib::read_tsp(&path)?;
+        let mut problem = Self::new(instance.name.clone(), instance.distance_matrix());
+
+        let optimal_tour = path.as_ref().with_extension("opt.tour");
+        if optimal_tour.exists() {
+            let tour = tsplib::read_tour(optimal_tour)?;
+            problem.best_known = Some(tour_length(&tour.nodes, &problem.distances));
+        }
+
+        Ok(problem)
+    }
+
+    pub fn len(&self) -> usize {
+        self.distances.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.distances.is_empty()
+    }
+}
+
+impl Problem for TspProblem {}
+
+impl Distances for TspProblem {
+    fn distance(&self, from: usize, to: usize) -> f64 {
+        self.distances[from][to]
+    }
+}
+
+#[solution_attr]
+#[derive(Clone, Debug, DerivedSolution)]
+pub struct TspSolution {
+    pub tour: Vec<usize>,
+}
+
+impl TspSolution {
+    pub fn new(tour: Vec<usize>) -> Self {
+        Self {
+            tour,
+            eval: Evaluation::default(),
+        }
+    }
+
+    /// Visits the nodes in order of their numbers.
+    pub fn identity(problem: &TspProblem) -> Self {
+        Self::new((0..problem.len()).collect())
+    }
+
+    pub fn random<R: Rng + ?Sized>(problem: &TspProblem, rng: &mut R) -> Self {
+        let mut tour: Vec<usize> = (0..problem.len()).collect();
+        tour.shuffle(rng);
+        Self::new(tour)
+    }
+}
+
+impl Chromosome for TspSolution {
+    type Gene = usize;
+
+    fn genes(&self) -> &[usize] {
+        &self.tour
+    }
+
+    fn genes_mut(&mut self) -> &mut [usize] {
+        &mut self.tour
+    }
+}
+
+impl AsCsvRow for TspSolution {
+    fn as_row(&self, i: usize) -> String {
+        format!("{},{}", i, self.get_value())
+    }
+}
+
+impl DeltaMove<TspProblem, TspSolution> for Move {
+    fn delta(&self, problem: &TspProblem, before: &TspSolution) -> Option<Delta> {
+        Some(Delta {
+            penalty: 0.0,
+            value: self.tour_delta(&before.tour, problem),
+        })
+    }
+}
+
+/// Length of the closed tour, to be minimized.
+pub fn value(problem: &TspProblem, solution: &TspSolution) -> f64 {
+    tour_length(&solution.tour, problem)
+}
+
+/// Every permutation is a valid tour.
+pub fn penalty(_problem: &TspProblem, _solution: &TspSolution) -> f64 {
+    0.0
+}
+
+/// Change function applying a random 2-opt move.
+pub fn two_opt<R: Rng + ?Sized>(
+    solution: &mut TspSolution,
+    _problem: &TspProblem,
+    rng: &mut R,
+) -> Move {
+    let two_opt = Neighbourhood::TwoOpt.sample(solution.tour.len(), rng);
+    two_opt.apply(&mut solution.tour);
+    two_opt
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use super::{penalty, two_opt, value, TspProblem, TspSolution};
+    use crate::base::{Criterion, Solution};
+
+    #[test]
+    fn two_opt_deltas_match_full_evaluation() {
+        let mut rng = StdRng::seed_from_u64(16);
+        let problem = TspProblem::random(12, &mut rng);
+        let mut criterion = Criterion::new(&penalty, &value, true);
+        criterion.check_delta = true;
+
+        let mut solution = TspSolution::random(&problem, &mut rng);
+        criterion.evaluate(&problem, &mut solution);
+        for _ in 0..100 {
+            let before = solution.clone();
+            let change = two_opt(&mut solution, &problem, &mut rng);
+            criterion.evaluate_move(&problem, &before, &mut solution, &change);
+        }
+
+        let mut sorted = solution.tour.clone();
+        sorted.sort();
+        assert_eq!((0..12).collect::<Vec<_>>(), sorted);
+        assert!(solution.get_value() > 0.0);
+    }
+}
@@ -8,14 +8,16 @@ use std::{
 use rand::{
     distributions::Uniform,
     prelude::{Distribution, ThreadRng},
-    thread_rng, Rng,
+    thread_rng, Rng, SeedableRng,
 };
 
 use crate::{
     annealing::stop::StopCriteria,
     base::{
-        solution_attr, Criterion, DerivedSolution, Evaluation, OptAlgorithm, Problem, Solution,
+        diversity, notify, solution_attr, CheckpointFn, Criterion, CriterionState, DerivedSolution,
+        Encoding, Evaluation, Event, Iteration, Observer, OptAlgorithm, Problem, Solution,
     },
+    Error, Result,
 };
 
 pub struct Suggestions {
@@ -29,6 +31,8 @@ impl Suggestions {
     }
 }
 
+/// Interactive hook called after every step, e.g. to render the swarm. Its `Suggestions` can pause
+/// or end the run, use `register_observer` to only watch it.
 pub type SwarmInsightFn =
     dyn FnMut(&FnProblem<RangeInclusive<f64>>, &Vec<Particle>, usize, bool) -> Suggestions; //Slow
 
@@ -74,66 +78,110 @@ where
 
 #[solution_attr]
 #[derive(Clone, DerivedSolution)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Particle {
     best_local_index: usize,
-    velocity_x: f64,
-    velocity_y: f64,
-    pub x: f64,
-    pub y: f64,
+    velocity: Vec<f64>,
+    pub position: Vec<f64>,
 }
 
 impl Particle {
-    pub fn new(x: f64, y: f64) -> Self {
+    pub fn new(position: Vec<f64>) -> Self {
         Self {
-            x,
-            y,
             best_local_index: 0,
-            velocity_x: 0.0,
-            velocity_y: 0.0,
+            velocity: vec![0.0; position.len()],
+            position,
             eval: Evaluation {
                 value: 0.0,
                 is_feasible: true,
+                ..Default::default()
             },
         }
     }
-    pub fn update_position(&mut self, problem: &FnProblem<RangeInclusive<f64>>) {
-        self.x += self.velocity_x;
-        self.y += self.velocity_y;
 
-        let x_min = min_value_of_range(&problem.x_range).unwrap();
-        let x_max = max_value_of_range(&problem.x_range).unwrap();
+    /// First coordinate, for 2-D problems.
+    pub fn x(&self) -> f64 {
+        self.position[0]
+    }
+
+    /// Second coordinate, for 2-D problems.
+    pub fn y(&self) -> f64 {
+        self.position[1]
+    }
+
+    /// Position as a solution of a problem built with `FnProblem::from_encoding`.
+    pub fn decode<E: Encoding>(&self) -> E {
+        E::from_slice(&self.position)
+    }
 
-        let y_min = min_value_of_range(&problem.y_range).unwrap();
-        let y_max = max_value_of_range(&problem.y_range).unwrap();
+    pub fn update_position(&mut self, problem: &FnProblem<RangeInclusive<f64>>) -> Result<()> {
+        let dimensions = self.position.iter_mut().zip(&self.velocity);
+        for ((coordinate, velocity), range) in dimensions.zip(&problem.bounds) {
+            let min = min_value_of_range(range).ok_or(Error::InvalidRange)?;
+            let max = max_value_of_range(range).ok_or(Error::InvalidRange)?;
 
-        self.x = f64::clamp(self.x, x_min, x_max);
-        self.y = f64::clamp(self.y, y_min, y_max);
+            *coordinate = f64::clamp(*coordinate + velocity, min, max);
+        }
+        Ok(())
     }
 }
-#[derive(Copy, Clone)]
+
+/// Continuous problem with one range of allowed values per dimension.
+#[derive(Clone)]
 pub struct FnProblem<R: RangeBounds<f64>> {
     pub id: u32,
-    pub x_range: R,
-    pub y_range: R,
+    pub bounds: Vec<R>,
 
-    points_distribution_x: Uniform<f64>,
-    points_distribution_y: Uniform<f64>,
+    points_distributions: Vec<Uniform<f64>>,
 }
 
 impl FnProblem<RangeInclusive<f64>> {
-    pub fn new(id: u32, x_range: RangeInclusive<f64>, y_range: RangeInclusive<f64>) -> Self {
-        Self {
-            points_distribution_x: Uniform::from(x_range.clone()),
-            points_distribution_y: Uniform::from(y_range.clone()),
-            x_range: x_range,
-            y_range: y_range,
-            id,
+    pub fn new(id: u32, bounds: Vec<RangeInclusive<f64>>) -> Result<Self> {
+        if bounds.is_empty() || bounds.iter().any(|range| range.is_empty()) {
+            return Err(Error::InvalidRange);
         }
+
+        Ok(Self {
+            points_distributions: bounds.iter().cloned().map(Uniform::from).collect(),
+            bounds,
+            id,
+        })
+    }
+
+    pub fn new_2d(
+        id: u32,
+        x_range: RangeInclusive<f64>,
+        y_range: RangeInclusive<f64>,
+    ) -> Result<Self> {
+        Self::new(id, vec![x_range, y_range])
+    }
+
+    /// One dimension per gene of `E`, see `Particle::decode`.
+    pub fn from_encoding<E: Encoding>(id: u32) -> Result<Self> {
+        Self::new(id, E::bounds())
+    }
+}
+
+impl<R: RangeBounds<f64>> FnProblem<R> {
+    pub fn dimensions(&self) -> usize {
+        self.bounds.len()
     }
 }
 
 impl<R: RangeBounds<f64>> Problem for FnProblem<R> {}
 
+/// Swarm after `iteration` simulation steps, see `ParticleSwarm::resume`.
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SwarmCheckpoint<SC, R> {
+    pub particles: Vec<Particle>,
+    pub best_global_index: usize,
+    pub stop_criteria: SC,
+    pub rng: R,
+    pub iteration: u32,
+    pub criterion: CriterionState,
+}
+
 struct OptParams {
     skip_simulation: bool,
 }
@@ -144,18 +192,21 @@ impl OptParams {
     }
 }
 
-pub struct ParticleSwarm<'a, SC: StopCriteria> {
+pub struct ParticleSwarm<'a, SC: StopCriteria, R: Rng = ThreadRng> {
     pub particles: Vec<Particle>,
     best_global_index: usize,
     stop_criteria: SC,
     local_attraction: f64,
     global_attraction: f64,
     inertia: f64,
-    rng: ThreadRng,
+    rng: R,
     insight: Option<&'a mut SwarmInsightFn>,
+    observer: Option<&'a mut dyn Observer<Particle>>,
+    checkpoint: Option<&'a mut CheckpointFn<'a, SwarmCheckpoint<SC, R>>>,
+    checkpoint_every: u32,
 }
 
-impl<'a, SC> ParticleSwarm<'a, SC>
+impl<'a, SC> ParticleSwarm<'a, SC, ThreadRng>
 where
     SC: StopCriteria,
 {
@@ -169,6 +220,9 @@ where
             local_attraction: 0.5,
             inertia: 0.05,
             insight: None,
+            observer: None,
+            checkpoint: None,
+            checkpoint_every: 0,
             rng,
         }
     }
@@ -189,28 +243,93 @@ where
             local_attraction,
             inertia,
             insight: None,
+            observer: None,
+            checkpoint: None,
+            checkpoint_every: 0,
+            rng,
+        }
+    }
+
+    /// Replaces the generator, pass a seeded one (e.g. `StdRng::seed_from_u64`) to make a run reproducible.
+    /// Checkpoints need a seedable generator, so none can be registered before this call.
+    pub fn with_rng<T: Rng>(self, rng: T) -> ParticleSwarm<'a, SC, T> {
+        ParticleSwarm {
+            particles: self.particles,
+            best_global_index: self.best_global_index,
+            stop_criteria: self.stop_criteria,
+            local_attraction: self.local_attraction,
+            global_attraction: self.global_attraction,
+            inertia: self.inertia,
             rng,
+            insight: self.insight,
+            observer: self.observer,
+            checkpoint: None,
+            checkpoint_every: 0,
         }
     }
+}
 
+impl<'a, SC, R> ParticleSwarm<'a, SC, R>
+where
+    SC: StopCriteria,
+    R: Rng,
+{
     pub fn register_insight(&mut self, f: &'a mut SwarmInsightFn) {
         self.insight = Some(f);
     }
 
+    pub fn register_observer(&mut self, observer: &'a mut dyn Observer<Particle>) {
+        self.observer = Some(observer);
+    }
+
+    /// Calls `checkpoint` every `every` simulation steps with a snapshot the run can be resumed from.
+    /// Needs a seedable generator, clones of the thread generator share its state.
+    pub fn register_checkpoint(
+        &mut self,
+        every: u32,
+        checkpoint: &'a mut CheckpointFn<'a, SwarmCheckpoint<SC, R>>,
+    ) where
+        R: SeedableRng,
+    {
+        self.checkpoint = Some(checkpoint);
+        self.checkpoint_every = every.max(1);
+    }
+
+    /// Continues a run from `checkpoint`, producing the same result as the run it was taken from.
+    /// The time limit and the cancel flag of the current stop criteria are kept.
+    pub fn resume(
+        &mut self,
+        checkpoint: SwarmCheckpoint<SC, R>,
+        problem: FnProblem<RangeInclusive<f64>>,
+        criterion: &mut Criterion<FnProblem<RangeInclusive<f64>>, Particle>,
+    ) -> Result<Vec<Particle>>
+    where
+        R: SeedableRng + Clone,
+    {
+        self.particles = checkpoint.particles;
+        self.best_global_index = checkpoint.best_global_index;
+        self.stop_criteria.resume_from(&checkpoint.stop_criteria);
+        self.rng = checkpoint.rng;
+        criterion.restore(checkpoint.criterion);
+
+        self.run(&problem, criterion, checkpoint.iteration)
+    }
+
     fn reset(&mut self) {
         self.stop_criteria.reset();
         self.best_global_index = 0;
     }
 
-    fn is_better(&self, this: usize, known_best_index: usize, is_minimization: bool) -> bool {
-        let best_value = self.particles[known_best_index].get_value();
-        let current_value = self.particles[this].get_value();
-
-        return if is_minimization {
-            current_value < best_value
-        } else {
-            current_value > best_value
-        };
+    fn is_better(
+        &self,
+        this: usize,
+        known_best_index: usize,
+        criterion: &Criterion<FnProblem<RangeInclusive<f64>>, Particle>,
+    ) -> bool {
+        criterion.is_first_better(
+            self.particles[this].get_eval(),
+            self.particles[known_best_index].get_eval(),
+        )
     }
 
     fn initialize(
@@ -221,19 +340,24 @@ where
         self.particles.clear();
 
         for i in 0..self.particles.capacity() {
+            let velocity = (0..problem.dimensions()).map(|_| self.rng.gen()).collect();
+            let position = problem
+                .points_distributions
+                .iter()
+                .map(|distribution| distribution.sample(&mut self.rng))
+                .collect();
             let mut particle = Particle {
                 best_local_index: i,
-                velocity_x: self.rng.gen(),
-                velocity_y: self.rng.gen(),
-                x: problem.points_distribution_x.sample(&mut self.rng),
-                y: problem.points_distribution_y.sample(&mut self.rng),
+                velocity,
+                position,
                 eval: Evaluation::default(),
             };
 
             criterion.evaluate(&problem, &mut particle);
+            self.stop_criteria.evaluated(1);
             self.particles.push(particle);
 
-            if self.is_better(i, self.best_global_index, criterion.is_minimization) {
+            if self.is_better(i, self.best_global_index, criterion) {
                 self.best_global_index = i;
             }
         }
@@ -243,42 +367,40 @@ where
         &mut self,
         problem: &FnProblem<RangeInclusive<f64>>,
         criterion: &mut Criterion<FnProblem<RangeInclusive<f64>>, Particle>,
-    ) {
+    ) -> Result<()> {
         for i in 0..self.particles.len() {
             //Pick random parameters r_i and r_g
             let r_local: f64 = self.rng.gen();
             let r_global: f64 = self.rng.gen();
 
-            //Update x velocity
+            //Update velocity in every dimension
             let particle = &self.particles[i];
             let best_local = &self.particles[particle.best_local_index];
             let best_global = &self.particles[self.best_global_index];
-            let local = self.local_attraction * r_local * (best_local.x - particle.x);
-            let global = self.global_attraction * r_global * (best_global.x - particle.x);
+            let velocity: Vec<f64> = (0..particle.position.len())
+                .map(|d| {
+                    let local = self.local_attraction
+                        * r_local
+                        * (best_local.position[d] - particle.position[d]);
+                    let global = self.global_attraction
+                        * r_global
+                        * (best_global.position[d] - particle.position[d]);
+                    self.inertia * particle.velocity[d] + local + global
+                })
+                .collect();
             let particle = &mut self.particles[i];
-            particle.velocity_x = self.inertia * particle.velocity_x + local + global;
-
-            //Update y velocity
-            let particle = &self.particles[i];
-            let best_local = &self.particles[particle.best_local_index];
-            let best_global = &self.particles[self.best_global_index];
-            let local = self.local_attraction * r_local * (best_local.y - particle.y);
-            let global = self.global_attraction * r_global * (best_global.y - particle.y);
-            let particle = &mut self.particles[i];
-            particle.velocity_y = self.inertia * particle.velocity_y + local + global;
+            particle.velocity = velocity;
 
             //Update position in search space according to velocity
-            particle.update_position(problem);
+            particle.update_position(problem)?;
 
             criterion.evaluate(problem, particle);
 
             let particle = &self.particles[i];
 
             let best_local_index = particle.best_local_index;
-            let is_local_better = self.is_better(i, best_local_index, criterion.is_minimization);
-
-            let is_this_better =
-                self.is_better(i, self.best_global_index, criterion.is_minimization);
+            let is_local_better = self.is_better(i, best_local_index, criterion);
+            let is_this_better = self.is_better(i, self.best_global_index, criterion);
 
             let particle = &mut self.particles[i];
             if is_local_better {
@@ -288,34 +410,74 @@ where
                 self.best_global_index = i;
             }
         }
+        self.stop_criteria.evaluated(self.particles.len());
+        Ok(())
     }
-}
 
-impl<'a, SC> OptAlgorithm<'a, FnProblem<RangeInclusive<f64>>, Particle> for ParticleSwarm<'a, SC>
-where
-    SC: StopCriteria,
-{
-    fn solve(
+    fn run(
         &mut self,
-        problem: FnProblem<RangeInclusive<f64>>,
+        problem: &FnProblem<RangeInclusive<f64>>,
         criterion: &mut Criterion<FnProblem<RangeInclusive<f64>>, Particle>,
-    ) -> Vec<Particle> {
-        self.reset();
-        self.initialize(&problem, criterion);
-
-        let best_value = self.particles[self.best_global_index].get_value();
-
+        mut iteration: u32,
+    ) -> Result<Vec<Particle>>
+    where
+        R: Clone,
+    {
         let mut skip_simulation = false;
+        let mut best = self
+            .particles
+            .get(self.best_global_index)
+            .ok_or(Error::EmptyPopulation)?
+            .clone();
+        notify(&mut self.observer, &Event::RunStarted);
 
         while !self.stop_criteria.should_stop() {
             if !skip_simulation {
-                self.simulate(&problem, criterion);
-                self.stop_criteria.update(best_value);
+                self.simulate(problem, criterion)?;
+                self.stop_criteria
+                    .update(self.particles[self.best_global_index].get_eval());
+
+                let current = &self.particles[self.best_global_index];
+                if criterion.is_first_better(current.get_eval(), best.get_eval()) {
+                    best = current.clone();
+                    notify(
+                        &mut self.observer,
+                        &Event::NewBest {
+                            iteration,
+                            best: &best,
+                        },
+                    );
+                }
+                notify(
+                    &mut self.observer,
+                    &Event::IterationFinished(Iteration {
+                        iteration,
+                        best: &best,
+                        current,
+                        temperature: None,
+                        diversity: Some(diversity(&self.particles)),
+                    }),
+                );
+                criterion.next_iteration(current.get_eval());
+                iteration += 1;
+
+                if let Some(f) = &mut self.checkpoint {
+                    if iteration % self.checkpoint_every == 0 {
+                        f(&SwarmCheckpoint {
+                            particles: self.particles.clone(),
+                            best_global_index: self.best_global_index,
+                            stop_criteria: self.stop_criteria.clone(),
+                            rng: self.rng.clone(),
+                            iteration,
+                            criterion: criterion.state(),
+                        });
+                    }
+                }
             }
 
             let suggestions = match &mut self.insight {
                 Some(f) => {
-                    let x = f(&problem, &self.particles, self.best_global_index, false);
+                    let x = f(problem, &self.particles, self.best_global_index, false);
                     Some(x)
                 }
                 _ => None,
@@ -332,7 +494,33 @@ where
                 break;
             }
         }
-        vec![self.particles[self.best_global_index].clone()]
+
+        notify(
+            &mut self.observer,
+            &Event::RunFinished {
+                iterations: iteration,
+                best: &best,
+            },
+        );
+        Ok(vec![best])
+    }
+}
+
+impl<'a, SC, R> OptAlgorithm<'a, FnProblem<RangeInclusive<f64>>, Particle>
+    for ParticleSwarm<'a, SC, R>
+where
+    SC: StopCriteria,
+    R: Rng + Clone,
+{
+    fn solve(
+        &mut self,
+        problem: FnProblem<RangeInclusive<f64>>,
+        criterion: &mut Criterion<FnProblem<RangeInclusive<f64>>, Particle>,
+    ) -> Result<Vec<Particle>> {
+        self.reset();
+        self.initialize(&problem, criterion);
+
+        self.run(&problem, criterion, 0)
     }
 
     fn reset(&mut self) {
@@ -340,7 +528,7 @@ where
     }
 }
 
-impl<'a, SC: StopCriteria> Display for ParticleSwarm<'a, SC> {
+impl<'a, SC: StopCriteria, R: Rng> Display for ParticleSwarm<'a, SC, R> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(
             f,
@@ -353,3 +541,147 @@ impl<'a, SC: StopCriteria> Display for ParticleSwarm<'a, SC> {
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use super::{FnProblem, Particle, ParticleSwarm, SwarmCheckpoint};
+    use crate::{
+        annealing::stop::MaxSteps,
+        base::{Criterion, Encoding, Event, OptAlgorithm, Solution},
+        Error,
+    };
+
+    fn penalty(_: &FnProblem<std::ops::RangeInclusive<f64>>, _: &Particle) -> f64 {
+        0.0
+    }
+
+    fn value(_: &FnProblem<std::ops::RangeInclusive<f64>>, particle: &Particle) -> f64 {
+        particle.position.iter().map(|x| x.powi(2)).sum()
+    }
+
+    #[test]
+    fn resume_continues_bit_for_bit() {
+        let problem = FnProblem::new_2d(0, -5.0..=5.0, -5.0..=5.0).unwrap();
+        let mut criterion = Criterion::new(&penalty, &value, true);
+        let mut snapshots = vec![];
+        let mut save = |checkpoint: &SwarmCheckpoint<_, _>| snapshots.push(checkpoint.clone());
+        let mut swarm =
+            ParticleSwarm::new(20, MaxSteps::new(60)).with_rng(StdRng::seed_from_u64(11));
+        swarm.register_checkpoint(20, &mut save);
+        let uninterrupted = swarm.solve(problem.clone(), &mut criterion).unwrap();
+        drop(swarm);
+
+        assert_eq!(3, snapshots.len());
+        let mut resumed =
+            ParticleSwarm::new(20, MaxSteps::new(60)).with_rng(StdRng::seed_from_u64(0));
+        let result = resumed
+            .resume(snapshots[1].clone(), problem, &mut criterion)
+            .unwrap();
+
+        assert_eq!(uninterrupted[0].position, result[0].position);
+        assert_eq!(uninterrupted[0].get_value(), result[0].get_value());
+    }
+
+    #[test]
+    fn solve_works_in_many_dimensions() {
+        let problem = FnProblem::new(0, vec![-5.0..=5.0; 10]).unwrap();
+        let mut criterion = Criterion::new(&penalty, &value, true);
+        let mut swarm =
+            ParticleSwarm::new(50, MaxSteps::new(300)).with_rng(StdRng::seed_from_u64(3));
+
+        let best = swarm.solve(problem, &mut criterion).unwrap().remove(0);
+
+        assert_eq!(10, best.position.len());
+        assert!(best.get_value() < 1.0, "{}", best.get_value());
+    }
+
+    #[derive(Encoding)]
+    struct Layout {
+        #[gene(range = 0.0..=2.0)]
+        width: f64,
+        #[gene(range = 1..=4)]
+        columns: u8,
+    }
+
+    #[test]
+    fn solves_derived_encoding() {
+        let problem = FnProblem::from_encoding::<Layout>(0).unwrap();
+        let penalty = |_: &FnProblem<_>, _: &Particle| 0.0;
+        let value = |_: &FnProblem<_>, particle: &Particle| {
+            let layout: Layout = particle.decode();
+            (layout.width - 0.5).powi(2) + (f64::from(layout.columns) - 3.0).powi(2)
+        };
+        let mut criterion = Criterion::new(&penalty, &value, true);
+        let mut swarm =
+            ParticleSwarm::new(20, MaxSteps::new(100)).with_rng(StdRng::seed_from_u64(3));
+
+        let layout: Layout = swarm.solve(problem, &mut criterion).unwrap()[0].decode();
+
+        assert!((layout.width - 0.5).abs() < 0.1, "{}", layout.width);
+        assert_eq!(3, layout.columns);
+    }
+
+    #[test]
+    fn best_particle_is_feasible_first() {
+        let problem = FnProblem::new(0, vec![-10.0..=10.0]).unwrap();
+        // Feasible values are at least 100, violations at most 5
+        let penalty = |_: &FnProblem<_>, particle: &Particle| f64::max(particle.x() - 5.0, 0.0);
+        let value = |_: &FnProblem<_>, particle: &Particle| particle.x().powi(2) + 100.0;
+        let mut criterion = Criterion::new(&penalty, &value, true);
+        let mut swarm =
+            ParticleSwarm::new(20, MaxSteps::new(50)).with_rng(StdRng::seed_from_u64(3));
+
+        let best = swarm.solve(problem, &mut criterion).unwrap().remove(0);
+
+        assert!(best.get_eval().is_feasible, "{}", best.x());
+        assert!(best.get_value() < 101.0, "{}", best.get_value());
+    }
+
+    #[test]
+    fn returns_the_reported_best() {
+        let problem = FnProblem::new_2d(0, -5.0..=5.0, -5.0..=5.0).unwrap();
+        let mut criterion = Criterion::new(&penalty, &value, true);
+        let mut reported = None;
+        let mut finished = |event: &Event<Particle>| {
+            if let Event::RunFinished { best, .. } = event {
+                reported = Some(best.position.clone());
+            }
+        };
+        let mut swarm =
+            ParticleSwarm::new(10, MaxSteps::new(40)).with_rng(StdRng::seed_from_u64(5));
+        swarm.register_observer(&mut finished);
+
+        let best = swarm.solve(problem, &mut criterion).unwrap().remove(0);
+        drop(swarm);
+
+        assert_eq!(Some(best.position), reported);
+    }
+
+    #[test]
+    fn empty_swarm_is_an_error() {
+        let problem = FnProblem::new_2d(0, -5.0..=5.0, -5.0..=5.0).unwrap();
+        let mut criterion = Criterion::new(&penalty, &value, true);
+        let mut swarm = ParticleSwarm::new(0, MaxSteps::new(10)).with_rng(StdRng::seed_from_u64(5));
+        let result = swarm.solve(problem.clone(), &mut criterion);
+        assert!(matches!(result, Err(Error::EmptyPopulation)));
+
+        let checkpoint = SwarmCheckpoint {
+            particles: vec![],
+            best_global_index: 0,
+            stop_criteria: MaxSteps::new(10),
+            rng: StdRng::seed_from_u64(5),
+            iteration: 0,
+            criterion: criterion.state(),
+        };
+        let result = swarm.resume(checkpoint, problem, &mut criterion);
+        assert!(matches!(result, Err(Error::EmptyPopulation)));
+    }
+
+    #[test]
+    fn new_rejects_empty_bounds() {
+        assert!(FnProblem::new(0, vec![]).is_err());
+        assert!(FnProblem::new(0, vec![-1.0..=1.0, 1.0..=-1.0]).is_err());
+    }
+}
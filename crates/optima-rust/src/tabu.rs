@@ -0,0 +1,304 @@
+use std::{collections::VecDeque, fmt::Display};
+
+use rand::{prelude::ThreadRng, thread_rng, Rng};
+
+use crate::{
+    annealing::stop::StopCriteria,
+    base::{notify, Criterion, Event, Iteration, Observer, OptAlgorithm, Problem, Solution},
+    Result,
+};
+
+/// Generates neighbours of the current solution, each paired with the move that produced it.
+/// Moves are what the tabu list remembers, so they should describe the change (e.g. swapped
+/// indexes) rather than the whole solution.
+pub type NeighbourhoodFn<S, P, M, R = ThreadRng> = dyn Fn(&S, &P, &mut R) -> Vec<(S, M)>;
+
+pub struct TabuSearch<'a, P, S, M, SC, R = ThreadRng>
+where
+    P: Problem,
+    S: Solution,
+    M: PartialEq,
+    SC: StopCriteria,
+    R: Rng,
+{
+    stop_criteria: SC,
+    tenure: usize,
+    neighbourhood: &'a NeighbourhoodFn<S, P, M, R>,
+    initial_solution: &'a S,
+    tabu_list: VecDeque<M>,
+    observer: Option<&'a mut dyn Observer<S>>,
+    rng: R,
+}
+
+impl<'a, P, S, M, SC> TabuSearch<'a, P, S, M, SC, ThreadRng>
+where
+    P: Problem,
+    S: Solution,
+    M: PartialEq,
+    SC: StopCriteria,
+{
+    pub fn new(
+        initial_solution: &'a S,
+        stop_criteria: SC,
+        tenure: usize,
+        neighbourhood: &'a NeighbourhoodFn<S, P, M>,
+    ) -> Self {
+        Self::with_rng(
+            initial_solution,
+            stop_criteria,
+            tenure,
+            neighbourhood,
+            thread_rng(),
+        )
+    }
+}
+
+impl<'a, P, S, M, SC, R> TabuSearch<'a, P, S, M, SC, R>
+where
+    P: Problem,
+    S: Solution,
+    M: PartialEq,
+    SC: StopCriteria,
+    R: Rng,
+{
+    /// `tenure` is the number of iterations a move stays forbidden after being made.
+    pub fn with_rng(
+        initial_solution: &'a S,
+        stop_criteria: SC,
+        tenure: usize,
+        neighbourhood: &'a NeighbourhoodFn<S, P, M, R>,
+        rng: R,
+    ) -> Self {
+        Self {
+            stop_criteria,
+            tenure,
+            neighbourhood,
+            initial_solution,
+            tabu_list: VecDeque::with_capacity(tenure + 1),
+            observer: None,
+            rng,
+        }
+    }
+
+    pub fn register_observer(&mut self, observer: &'a mut dyn Observer<S>) {
+        self.observer = Some(observer);
+    }
+
+    fn is_tabu(&self, mv: &M) -> bool {
+        self.tabu_list.contains(mv)
+    }
+
+    fn make_tabu(&mut self, mv: M) {
+        if self.tenure == 0 {
+            return;
+        }
+        self.tabu_list.push_back(mv);
+        while self.tabu_list.len() > self.tenure {
+            self.tabu_list.pop_front();
+        }
+    }
+}
+
+impl<'a, P, S, M, SC, R> OptAlgorithm<'a, P, S> for TabuSearch<'a, P, S, M, SC, R>
+where
+    P: Problem,
+    S: Solution,
+    M: PartialEq,
+    SC: StopCriteria,
+    R: Rng,
+{
+    fn solve(&mut self, problem: P, criterion: &mut Criterion<P, S>) -> Result<Vec<S>> {
+        self.reset();
+
+        let mut solution = self.initial_solution.clone();
+        criterion.evaluate(&problem, &mut solution);
+        self.stop_criteria.evaluated(1);
+        let mut best = solution.clone();
+
+        notify(&mut self.observer, &Event::RunStarted);
+
+        let mut counter = 0;
+        while !self.stop_criteria.should_stop() {
+            let mut chosen: Option<(S, M)> = None;
+
+            for (mut neighbour, mv) in (self.neighbourhood)(&solution, &problem, &mut self.rng) {
+                criterion.evaluate(&problem, &mut neighbour);
+                self.stop_criteria.evaluated(1);
+
+                //Aspiration: tabu move is allowed if it beats the best solution found so far
+                let aspires = criterion.is_first_better(neighbour.get_eval(), best.get_eval());
+                if self.is_tabu(&mv) && !aspires {
+                    continue;
+                }
+
+                let is_better = match &chosen {
+                    Some((current, _)) => {
+                        criterion.is_first_better(neighbour.get_eval(), current.get_eval())
+                    }
+                    None => true,
+                };
+                if is_better {
+                    chosen = Some((neighbour, mv));
+                }
+            }
+
+            if let Some((neighbour, mv)) = chosen {
+                solution = neighbour;
+                self.make_tabu(mv);
+                notify(
+                    &mut self.observer,
+                    &Event::Accepted {
+                        iteration: counter,
+                        candidate: &solution,
+                    },
+                );
+
+                if criterion.is_first_better(solution.get_eval(), best.get_eval()) {
+                    best = solution.clone();
+                    notify(
+                        &mut self.observer,
+                        &Event::NewBest {
+                            iteration: counter,
+                            best: &best,
+                        },
+                    );
+                }
+            } else {
+                //Every neighbour is tabu, stay in place and let the oldest move expire
+                self.tabu_list.pop_front();
+            }
+
+            notify(
+                &mut self.observer,
+                &Event::IterationFinished(Iteration {
+                    iteration: counter,
+                    best: &best,
+                    current: &solution,
+                    temperature: None,
+                    diversity: None,
+                }),
+            );
+            criterion.next_iteration(solution.get_eval());
+            counter += 1;
+            self.stop_criteria.update(solution.get_eval());
+        }
+
+        notify(
+            &mut self.observer,
+            &Event::RunFinished {
+                iterations: counter,
+                best: &best,
+            },
+        );
+
+        Ok(vec![best])
+    }
+
+    fn reset(&mut self) {
+        self.stop_criteria.reset();
+        self.tabu_list.clear();
+    }
+}
+
+impl<'a, P, S, M, SC, R> Display for TabuSearch<'a, P, S, M, SC, R>
+where
+    P: Problem,
+    S: Solution,
+    M: PartialEq,
+    SC: StopCriteria,
+    R: Rng,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Tabu search: tenure: {}\n{}",
+            self.tenure, self.stop_criteria
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::Rng;
+
+    use super::TabuSearch;
+    use crate::{
+        annealing::stop::MaxSteps,
+        base::{
+            solution_attr, Criterion, DerivedSolution, Evaluation, OptAlgorithm, Problem, Solution,
+        },
+    };
+
+    #[solution_attr]
+    #[derive(Clone, DerivedSolution)]
+    struct TestSolution {
+        x: usize,
+    }
+
+    // Local minimum at x = 2, global one at x = 8
+    #[derive(Clone, Copy)]
+    struct TestProblem {
+        values: [f64; 10],
+    }
+    impl Problem for TestProblem {}
+
+    fn penalty(_: &TestProblem, _: &TestSolution) -> f64 {
+        0.0
+    }
+
+    fn value(problem: &TestProblem, solution: &TestSolution) -> f64 {
+        problem.values[solution.x]
+    }
+
+    // The move is the visited position, so the search cannot walk straight back
+    fn neighbourhood<R: Rng>(
+        solution: &TestSolution,
+        problem: &TestProblem,
+        _: &mut R,
+    ) -> Vec<(TestSolution, usize)> {
+        let mut neighbours = vec![];
+        if solution.x > 0 {
+            neighbours.push(solution.x - 1);
+        }
+        if solution.x + 1 < problem.values.len() {
+            neighbours.push(solution.x + 1);
+        }
+
+        neighbours
+            .into_iter()
+            .map(|x| {
+                let neighbour = TestSolution {
+                    x,
+                    eval: Evaluation::default(),
+                };
+                (neighbour, x)
+            })
+            .collect()
+    }
+
+    fn solve(tenure: usize) -> TestSolution {
+        let problem = TestProblem {
+            values: [5.0, 3.0, 1.0, 2.0, 4.0, 3.0, 1.5, 0.5, 0.0, 1.0],
+        };
+        let initial = TestSolution {
+            x: 0,
+            eval: Evaluation::default(),
+        };
+        let mut criterion = Criterion::new(&penalty, &value, true);
+        let mut tabu = TabuSearch::new(&initial, MaxSteps::new(20), tenure, &neighbourhood);
+
+        tabu.solve(problem, &mut criterion).unwrap().remove(0)
+    }
+
+    #[test]
+    fn tabu_list_escapes_local_minimum() {
+        let best = solve(3);
+        assert_eq!(8, best.x);
+        assert_eq!(0.0, best.get_value());
+    }
+
+    #[test]
+    fn without_tenure_gets_stuck() {
+        assert_eq!(2, solve(0).x);
+    }
+}
@@ -7,6 +7,7 @@ use clap::ValueEnum;
 use image::io::Reader;
 use image::ImageBuffer;
 use image::Luma;
+use optima_rust::benchmarks::Benchmark;
 use optima_rust::swarm::max_value_of_range;
 use optima_rust::swarm::min_value_of_range;
 use optima_rust::swarm::Suggestions;
@@ -44,6 +45,14 @@ enum MathFnTwoArgs {
     Booth,
     Simple,
     Bukin,
+    Himmelblau,
+    Eggholder,
+    Rastrigin,
+    Ackley,
+    Rosenbrock,
+    Griewank,
+    Schwefel,
+    Levy,
 }
 
 #[derive(Parser)]
@@ -82,8 +91,6 @@ const HEIGHTMAP_SIZE: Vector3 = Vector3 {
     z: 16.0,
 };
 
-pub type MathFunction = dyn Fn(f64, f64) -> f64;
-
 #[derive(Clone)]
 struct Timer {
     start_time: f64,
@@ -116,32 +123,24 @@ impl Timer {
 }
 
 #[derive(Clone)]
-struct FnBench<'a, R: RangeBounds<f64>> {
-    pub name: String,
-    pub x_range: R,
-    pub y_range: R,
+struct FnBench {
+    pub benchmark: Benchmark,
     pub v_min_found: f64,
     pub v_max_found: f64,
-    pub global_minimum: (f64, f64, f64),
-    pub func: &'a MathFunction,
-}
-
-//Some function to optimize.
-//https://en.wikipedia.org/wiki/Test_functions_for_optimization
-fn booth(x: f64, y: f64) -> f64 {
-    f64::powf(x + 2.0 * y - 7.0, 2.0) + f64::powf(2.0 * x + y - 5.0, 2.0)
 }
 
-fn simple(x: f64, y: f64) -> f64 {
-    x * y
-}
-
-fn cormick(x: f64, y: f64) -> f64 {
-    (x + y).sin() + (x - y).powf(2.0) - 1.5 * x + 2.5 * y + 1.0
-}
+impl FnBench {
+    fn new(benchmark: Benchmark) -> Self {
+        Self {
+            benchmark,
+            v_min_found: f64::MAX,
+            v_max_found: f64::MIN,
+        }
+    }
 
-fn bukin(x: f64, y: f64) -> f64 {
-    100.0 * f64::sqrt((y - 0.01 * x.powf(2.0)).abs() + 0.01 * (x + 10.0).abs())
+    fn func(&self, x: f64, y: f64) -> f64 {
+        self.benchmark.evaluate(&[x, y])
+    }
 }
 
 fn percent(value: f64, min: f64, max: f64) -> f64 {
@@ -156,22 +155,22 @@ unsafe fn center_x(text: *const i8, font_size: i32, parent_x: u32, parent_w: u32
 unsafe fn draw_particle<R: RangeBounds<f64>>(
     p: &Particle,
     problem: &FnProblem<R>,
-    fn_to_optimize: &FnBench<R>,
+    fn_to_optimize: &FnBench,
     color: Color,
 ) {
-    let v = (fn_to_optimize.func)(p.x, p.y);
+    let v = fn_to_optimize.func(p.x(), p.y());
 
-    let x_min = min_value_of_range(&problem.x_range).unwrap();
-    let x_max = max_value_of_range(&problem.x_range).unwrap();
+    let x_min = min_value_of_range(&problem.bounds[0]).unwrap();
+    let x_max = max_value_of_range(&problem.bounds[0]).unwrap();
 
-    let y_min = min_value_of_range(&problem.y_range).unwrap();
-    let y_max = max_value_of_range(&problem.y_range).unwrap();
+    let y_min = min_value_of_range(&problem.bounds[1]).unwrap();
+    let y_max = max_value_of_range(&problem.bounds[1]).unwrap();
 
     let v_min = fn_to_optimize.v_min_found;
     let v_max = fn_to_optimize.v_max_found;
 
-    let p_x = percent(p.x, x_min, x_max) as f32;
-    let p_y = percent(p.y, y_min, y_max) as f32;
+    let p_x = percent(p.x(), x_min, x_max) as f32;
+    let p_y = percent(p.y(), y_min, y_max) as f32;
     let p_v = percent(v, v_min, v_max) as f32;
 
     let x = HEIGHTMAP_POS.x + (HEIGHTMAP_SIZE.x * p_x); //HEIGHTMAP_POS.x + HEIGHTMAP_SIZE.x * p_x;
@@ -220,18 +219,18 @@ unsafe fn load_font<T: AsRef<Path>>(path: T) -> Option<Font> {
     Some(font)
 }
 
-fn take_samples<R: RangeBounds<f64>>(
-    fn_to_optimize: &FnBench<R>,
+fn take_samples(
+    fn_to_optimize: &FnBench,
     w: u32,
     h: u32,
 ) -> (Vec<f64>, f64, f64) {
     let mut samples: Vec<f64> = Vec::with_capacity((w * h) as usize);
 
-    let x_min = min_value_of_range(&fn_to_optimize.x_range).unwrap();
-    let x_max = max_value_of_range(&fn_to_optimize.x_range).unwrap();
+    let x_min = min_value_of_range(&fn_to_optimize.benchmark.bounds[0]).unwrap();
+    let x_max = max_value_of_range(&fn_to_optimize.benchmark.bounds[0]).unwrap();
 
-    let y_min = min_value_of_range(&fn_to_optimize.y_range).unwrap();
-    let y_max = max_value_of_range(&fn_to_optimize.y_range).unwrap();
+    let y_min = min_value_of_range(&fn_to_optimize.benchmark.bounds[1]).unwrap();
+    let y_max = max_value_of_range(&fn_to_optimize.benchmark.bounds[1]).unwrap();
 
     let resolution_x = (x_max - x_min) / w as f64;
     let resolution_y = (y_max - y_min) / h as f64;
@@ -244,7 +243,7 @@ fn take_samples<R: RangeBounds<f64>>(
             let y = y_min + j as f64 * resolution_y;
             assert!(x <= x_max && x >= x_min);
             assert!(y <= y_max && y >= y_min);
-            let value = (fn_to_optimize.func)(x as f64, y as f64);
+            let value = fn_to_optimize.func(x as f64, y as f64);
             min_value = f64::min(min_value, value);
             max_value = f64::max(max_value, value);
             samples.push(value);
@@ -281,57 +280,33 @@ fn generate_heightmap(
 fn main() {
     let cli = Cli::parse();
 
-    let mut fn_to_optimize = match cli.method {
-        MathFnTwoArgs::Bukin => FnBench {
-            global_minimum: (-10.0, 1.0, 0.0),
-            name: String::from("bukin"),
-            x_range: (-15.0..=-5.0),
-            y_range: (-3.0..=3.0),
-            func: &bukin,
-            v_max_found: f64::MIN,
-            v_min_found: f64::MAX,
-        },
-        MathFnTwoArgs::Booth => FnBench {
-            global_minimum: (1.0, 3.0, 0.0),
-            name: String::from("booth"),
-            x_range: (-10.0..=10.0),
-            y_range: (-10.0..=10.0),
-            func: &booth,
-            v_max_found: f64::MIN,
-            v_min_found: f64::MAX,
-        },
-        MathFnTwoArgs::Simple => FnBench {
-            global_minimum: (-10.0, 10.0, -100.0),
-            name: String::from("simple"),
-            x_range: (-10.0..=10.0),
-            y_range: (-10.0..=10.0),
-            func: &simple,
-            v_max_found: f64::MIN,
-            v_min_found: f64::MAX,
-        },
-        MathFnTwoArgs::Cormick => FnBench {
-            global_minimum: (-0.54719, -1.54719, 0.0),
-            name: String::from("cormick"),
-            x_range: (-1.5..=4.0),
-            y_range: (-3.0..=4.0),
-            func: &cormick,
-            v_max_found: f64::MIN,
-            v_min_found: f64::MAX,
-        },
-    };
+    let mut fn_to_optimize = FnBench::new(match cli.method {
+        MathFnTwoArgs::Bukin => Benchmark::bukin(),
+        MathFnTwoArgs::Booth => Benchmark::booth(),
+        MathFnTwoArgs::Simple => Benchmark::simple(),
+        MathFnTwoArgs::Cormick => Benchmark::cormick(),
+        MathFnTwoArgs::Himmelblau => Benchmark::himmelblau(),
+        MathFnTwoArgs::Eggholder => Benchmark::eggholder(),
+        MathFnTwoArgs::Rastrigin => Benchmark::rastrigin(2),
+        MathFnTwoArgs::Ackley => Benchmark::ackley(2),
+        MathFnTwoArgs::Rosenbrock => Benchmark::rosenbrock(2),
+        MathFnTwoArgs::Griewank => Benchmark::griewank(2),
+        MathFnTwoArgs::Schwefel => Benchmark::schwefel(2),
+        MathFnTwoArgs::Levy => Benchmark::levy(2),
+    });
 
     let stop_criteria = NotGettingBetter::new(15000, 500, true);
 
     let mut swarm = ParticleSwarm::with_attraction(100, stop_criteria, 0.05, 0.04, 0.02);
 
-    let problem = FnProblem::new(
-        0,
-        fn_to_optimize.x_range.clone(),
-        fn_to_optimize.y_range.clone(),
-    );
+    let problem = fn_to_optimize
+        .benchmark
+        .problem(0)
+        .expect("Function has invalid bounds");
 
+    let benchmark = fn_to_optimize.benchmark.clone();
     let value_fn = |_problem: &FnProblem<RangeInclusive<f64>>, part: &Particle| {
-        (fn_to_optimize.func)(part.x, part.y)
+        benchmark.evaluate(&part.position)
     };
 
     let mut criterion = Criterion::new(&|_, _| 0.0, &value_fn, true);
@@ -345,10 +320,7 @@ fn main() {
 
         SetTargetFPS(refresh);
 
-        let known_optimum = Particle::new(
-            fn_to_optimize.global_minimum.0,
-            fn_to_optimize.global_minimum.1,
-        );
+        let known_optimum = Particle::new(fn_to_optimize.benchmark.minimum_at.clone());
 
         let font_size = 24.0;
         let b_font_size = 36.0;
@@ -372,10 +344,10 @@ fn main() {
 
         let mut known_optimum_text = CString::new(format!(
             "Known minimum: {}({:.3}, {:.3}) = {:.3}",
-            fn_to_optimize.name,
-            fn_to_optimize.global_minimum.0,
-            fn_to_optimize.global_minimum.1,
-            fn_to_optimize.global_minimum.2
+            fn_to_optimize.benchmark.name,
+            fn_to_optimize.benchmark.minimum_at[0],
+            fn_to_optimize.benchmark.minimum_at[1],
+            fn_to_optimize.benchmark.minimum
         ))
         .unwrap();
 
@@ -384,7 +356,7 @@ fn main() {
 
         let heightmap_filename = format!(
             "{}_{}_{}.png",
-            fn_to_optimize.name, HEIGHTMAP_W, HEIGHTMAP_H
+            fn_to_optimize.benchmark.name, HEIGHTMAP_W, HEIGHTMAP_H
         );
 
         let (samples, v_min, v_max) = take_samples(&fn_to_optimize, HEIGHTMAP_W, HEIGHTMAP_H);
@@ -484,10 +456,10 @@ fn main() {
                     &mut best_text,
                     &format!(
                         "Best found: {}({:.3}, {:.3}) = {:.3}",
-                        fn_to_optimize.name,
-                        best.x,
-                        best.y,
-                        (func.func)(best.x, best.y),
+                        func.benchmark.name,
+                        best.x(),
+                        best.y(),
+                        func.func(best.x(), best.y()),
                     ),
                 );
             }
@@ -543,7 +515,9 @@ fn main() {
 
         swarm.register_insight(draw_ui);
 
-        let particles = swarm.solve(problem.clone(), &mut criterion);
+        let particles = swarm
+            .solve(problem.clone(), &mut criterion)
+            .expect("Swarm failed");
 
         while !WindowShouldClose() {
             draw_ui(&problem, &particles, 0, true);
@@ -560,7 +534,7 @@ unsafe fn draw_particles_skip_best(
     particles: &Vec<Particle>,
     best_index: usize,
     problem: &FnProblem<RangeInclusive<f64>>,
-    func: &FnBench<RangeInclusive<f64>>,
+    func: &FnBench,
 ) {
     let mut i = 0;
     for p in particles {